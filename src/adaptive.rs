@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use crate::{Tecken, keystats, timing};
+
+// how much a key's error rate (0.0 - 1.0) adds to the weight of words containing it
+const ERROR_WEIGHT: f64 = 20.0;
+// how much a bigram being slower than average adds to the weight of words containing it
+const LATENCY_WEIGHT: f64 = 2.0;
+// samples needed before a bigram latency is trusted
+const MIN_BIGRAM_SAMPLES: u32 = 3;
+
+impl Tecken {
+    /// weight every word in the pool by the user's historically worst keys and slowest bigrams,
+    /// recalculated with every new word pool so the focus moves as keys improve
+    pub fn gen_word_weights(&mut self) {
+        let keys = keystats::load();
+        let ngrams = timing::load();
+
+        let char_error: HashMap<char, f64> = keys
+            .attempts
+            .keys()
+            .filter_map(|c| keys.error_rate(&[*c]).map(|r| (*c, r)))
+            .collect();
+
+        let bigrams = ngrams.slowest(2, MIN_BIGRAM_SAMPLES);
+        let mean_latency = if bigrams.is_empty() {
+            0.0
+        } else {
+            bigrams.iter().map(|(_, ms, _)| ms).sum::<f64>() / bigrams.len() as f64
+        };
+        // how much slower than average each bigram is, 0.5 = 50% slower
+        let bigram_slowness: HashMap<String, f64> = bigrams
+            .into_iter()
+            .map(|(g, ms, _)| (g, (ms / mean_latency - 1.0).max(0.0)))
+            .collect();
+
+        self.word_weights = self
+            .word_pool
+            .iter()
+            .map(|word| {
+                let chars: Vec<char> = word.chars().collect();
+                let errors: f64 = chars.iter().filter_map(|c| char_error.get(c)).sum();
+                let slowness: f64 = chars
+                    .windows(2)
+                    .filter_map(|w| bigram_slowness.get(&w.iter().collect::<String>()))
+                    .sum();
+                1.0 + errors * ERROR_WEIGHT + slowness * LATENCY_WEIGHT
+            })
+            .collect();
+    }
+}
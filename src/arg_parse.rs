@@ -1,10 +1,62 @@
 use std::io;
 
-use crate::{State, Tecken};
+use crate::{
+    DIFFICULTY_TIERS, MetaField, State, Tecken, WORDS, history,
+    config::Mode,
+    layout::Layout,
+    learn::{self, LESSONS},
+    quote::QuoteLength,
+    race::DEFAULT_PORT,
+    split::Split,
+    subcommands::help::APP_NAME,
+};
+
+// flags that change the words of the exercise or how a daily run is saved
+const DAILY_CONFLICTS: [&str; 13] = [
+    "-w",
+    "-t",
+    "-l",
+    "-d",
+    "-f",
+    "-p",
+    "--wordlist",
+    "--punctuation",
+    "--numbers",
+    "--adaptive",
+    "quote",
+    "learn",
+    "--seed",
+];
 
 impl Tecken {
     pub fn parse_args(&mut self) -> io::Result<()> {
-        let mut it = std::env::args().skip(1); // skip program name
+        let args: Vec<String> = std::env::args().skip(1).collect(); // skip program name
+
+        // the config file sets the defaults, the config subcommand works even when it is broken,
+        // help doesn't need it and stats only warns about it
+        let has = |cmd: &str| args.iter().any(|a| a == cmd);
+        // daily is the same text for everyone, so nothing may change the words
+        let daily_conflict = args
+            .iter()
+            .find(|a| DAILY_CONFLICTS.contains(&a.as_str()))
+            .cloned();
+        let config_mode = if has("config") || has("help") {
+            None
+        } else if has("stats") {
+            self.load_config().unwrap_or_else(|e| {
+                eprintln!("{APP_NAME}: {e}");
+                None
+            })
+        } else {
+            self.load_config()?
+        };
+        // signal that a mode was picked on the command line, replacing the config mode
+        let mut mode_set = false;
+        // signal that the words were picked on the command line, replacing the config word list
+        let mut language_set = false;
+        let mut wordlist_set = false;
+
+        let mut it = args.into_iter().peekable();
         while let Some(arg) = it.next() {
             match arg.as_str() {
                 "-w" => {
@@ -16,20 +68,256 @@ impl Tecken {
                         .parse::<i32>()
                         .unwrap_or(12);
                 }
+                "-t" => {
+                    mode_set = true;
+                    // use next if it exists and parses as u64, else default to 30
+                    let secs = it
+                        .next()
+                        .as_deref()
+                        .unwrap_or("30")
+                        .parse::<u64>()
+                        .unwrap_or(30);
+                    self.f_time_limit = Some(secs.max(1));
+                    self.state = State::Timed;
+                }
+                "-l" => {
+                    let lang = it.next().unwrap_or_default().to_lowercase();
+                    if !WORDS.iter().any(|(l, _)| *l == lang) {
+                        let available: Vec<&str> = WORDS.iter().map(|(l, _)| *l).collect();
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!(
+                                "unknown language '{lang}', available: {}",
+                                available.join(", ")
+                            ),
+                        ));
+                    }
+                    self.f_language = lang;
+                    language_set = true;
+                }
+                "-d" => {
+                    // use next if it exists and parses as usize, else default to 1
+                    let tier = it
+                        .next()
+                        .as_deref()
+                        .unwrap_or("1")
+                        .parse::<usize>()
+                        .unwrap_or(1);
+                    self.f_difficulty = tier.min(DIFFICULTY_TIERS.len() - 1);
+                }
+                "-f" => {
+                    let path = it.next().unwrap_or_default();
+                    self.load_text_file(&path)?;
+                }
+                "--wordlist" => {
+                    let path = it.next().unwrap_or_default();
+                    self.load_wordlist(&path)?;
+                    wordlist_set = true;
+                }
+                "--punctuation" => {
+                    self.f_punctuation = true;
+                }
+                "--numbers" => {
+                    self.f_numbers = true;
+                }
+                "--adaptive" => {
+                    self.f_adaptive = true;
+                }
+                "--layout" => {
+                    let name = it.next().unwrap_or_default();
+                    self.layout = Layout::load(&name)?;
+                }
+                "--emulate" => {
+                    self.f_emulate_layout = true;
+                }
+                "-k" => {
+                    self.f_show_keyboard = true;
+                }
+                "--seed" => {
+                    let seed = it.next().unwrap_or_default();
+                    let Ok(seed) = seed.parse::<u64>() else {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("invalid seed '{seed}', expected a positive number"),
+                        ));
+                    };
+                    self.f_seed = Some(seed);
+                }
+                "-p" => {
+                    // use next if it exists and parses as usize, else type the whole text
+                    self.f_passage_len = it.next().and_then(|n| n.parse::<usize>().ok());
+                }
+                "quote" => {
+                    mode_set = true;
+                    self.f_quote_mode = true;
+                    // optional length filter right after the subcommand
+                    if let Some(len) = it.peek().and_then(|a| QuoteLength::from_arg(a)) {
+                        self.f_quote_length = Some(len);
+                        it.next();
+                    }
+                }
+                "learn" => {
+                    mode_set = true;
+                    // optional lesson number, else continue with the highest unlocked lesson
+                    let unlocked = learn::load_progress();
+                    let lesson = match it.next_if(|a| a.parse::<usize>().is_ok()) {
+                        Some(n) => n.parse::<usize>().unwrap_or(1).max(1) - 1,
+                        None => unlocked,
+                    };
+                    if lesson >= LESSONS.len() || lesson > unlocked {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!(
+                                "lesson {} is locked, pass lesson {} first",
+                                lesson + 1,
+                                unlocked + 1
+                            ),
+                        ));
+                    }
+                    self.f_lesson = Some(lesson);
+                }
+                "host" => {
+                    mode_set = true;
+                    // optional port, else the default one
+                    self.f_host = Some(
+                        it.next_if(|a| a.parse::<u16>().is_ok())
+                            .and_then(|p| p.parse().ok())
+                            .unwrap_or(DEFAULT_PORT),
+                    );
+                }
+                "join" => {
+                    mode_set = true;
+                    let Some(addr) = it.next() else {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "join needs the address of the host, e.g. tecken join 192.168.1.2",
+                        ));
+                    };
+                    self.f_join = Some(addr);
+                }
+                "daily" => {
+                    // everyone gets the same exercise on the same day
+                    mode_set = true;
+                    let day = history::now() / 86400;
+                    self.f_daily = Some(day);
+                    self.f_seed = Some(day);
+                }
+                "split" => {
+                    mode_set = true;
+                    self.split = Some(Split::default());
+                }
+                "stats" => {
+                    match it.next_if(|a| a == "keys" || a == "ngrams").as_deref() {
+                        Some("keys") => self.f_stats_keys = true,
+                        Some("ngrams") => self.f_stats_ngrams = true,
+                        _ => {}
+                    }
+                    self.state = State::Stats;
+                }
+                "config" => {
+                    self.f_config_init = it.next_if(|a| a == "init").is_some();
+                    self.state = State::Config;
+                }
                 "help" => {
                     self.state = State::Help;
                     return Ok(());
                 }
                 "-e" => {
+                    mode_set = true;
                     self.f_endless_mode = true;
                     self.state = State::Endless;
                 }
                 "-h" => {
                     self.f_hide_metadata = true;
                 }
+                "-m" => {
+                    // comma separated list of fields, unknown fields are ignored
+                    let fields: Vec<MetaField> = it
+                        .next()
+                        .unwrap_or_default()
+                        .split(',')
+                        .filter_map(|f| MetaField::from_arg(f.trim()))
+                        .collect();
+                    if !fields.is_empty() {
+                        self.f_metadata_fields = fields;
+                    }
+                }
                 _ => {}
             }
         }
+        if language_set && !wordlist_set {
+            self.custom_word_pool.clear();
+        }
+        if !mode_set && self.state == State::Main {
+            match config_mode {
+                Some(Mode::Time(secs)) => {
+                    self.f_time_limit = Some(secs.max(1));
+                    self.state = State::Timed;
+                }
+                Some(Mode::Quote) => self.f_quote_mode = true,
+                Some(Mode::Endless) => {
+                    self.f_endless_mode = true;
+                    self.state = State::Endless;
+                }
+                Some(Mode::Words) | None => {}
+            }
+        }
+        if self.split.is_some()
+            && (self.f_time_limit.is_some()
+                || self.f_endless_mode
+                || self.f_host.is_some()
+                || self.f_join.is_some())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "split can't be combined with timed, endless or race mode",
+            ));
+        }
+        if let Some(name) = self
+            .f_lesson
+            .and_then(|lesson| learn::lesson_without_keys(&self.layout, lesson))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "layout '{}' has no keys for the {name} lesson",
+                    self.layout.name
+                ),
+            ));
+        }
+        if self.f_daily.is_some() && self.f_endless_mode {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "daily can't be combined with endless mode",
+            ));
+        }
+        if self.f_daily.is_some() {
+            if let Some(flag) = daily_conflict {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("daily can't be combined with {flag}, everyone gets the same exercise"),
+                ));
+            }
+            // the words from the config are ignored as well
+            self.f_word_quantity = 12;
+            self.f_language = String::from("english");
+            self.f_difficulty = 1;
+            self.f_punctuation = false;
+            self.f_numbers = false;
+            self.custom_word_pool.clear();
+        }
+        if self.custom_text.is_empty()
+            && self.state != State::Stats
+            && self.state != State::Config
+        {
+            self.load_text_stdin()?;
+        }
+        if self.f_daily.is_some() && !self.custom_text.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "daily can't be combined with piped text, everyone gets the same exercise",
+            ));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,251 @@
+use std::{env, fs, io, path::PathBuf};
+
+use crossterm::style::Color;
+use toml::{Table, Value};
+
+use crate::{
+    CLR_ENTRY_BG, CLR_ENTRY_FG, CLR_ERROR_BG, CLR_ERROR_FG, CLR_EXERCISE_BG, CLR_EXERCISE_FG,
+    CLR_FINGERS, CLR_GHOST_BG, CLR_GHOST_FG, CLR_KEY_FG, CLR_KEY_MISS_BG, CLR_KEY_NEXT_FG,
+    CLR_PB_FG, CLR_PLAYERS, DIFFICULTY_TIERS, MetaField, Tecken, WORDS, layout::Layout, utils,
+};
+
+const CONFIG_FILE: &str = "config.toml";
+/// commented default config written by `config init`
+pub const DEFAULT_CONFIG: &str = include_str!("static/config.toml");
+
+/// colors of the exercise, results and keyboard
+pub struct Theme {
+    pub exercise_fg: Color,
+    pub exercise_bg: Color,
+    pub entry_fg: Color,
+    pub entry_bg: Color,
+    pub error_fg: Color,
+    pub error_bg: Color,
+    /// personal bests and winners
+    pub highlight: Color,
+    pub ghost_fg: Color,
+    pub ghost_bg: Color,
+    pub key_fg: Color,
+    pub key_next_fg: Color,
+    pub key_miss_bg: Color,
+    /// next key highlight per finger, from left pinky to right pinky, then thumb
+    pub fingers: [Color; 9],
+    /// race markers per player id, repeated when there are more players
+    pub players: Vec<Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            exercise_fg: CLR_EXERCISE_FG,
+            exercise_bg: CLR_EXERCISE_BG,
+            entry_fg: CLR_ENTRY_FG,
+            entry_bg: CLR_ENTRY_BG,
+            error_fg: CLR_ERROR_FG,
+            error_bg: CLR_ERROR_BG,
+            highlight: CLR_PB_FG,
+            ghost_fg: CLR_GHOST_FG,
+            ghost_bg: CLR_GHOST_BG,
+            key_fg: CLR_KEY_FG,
+            key_next_fg: CLR_KEY_NEXT_FG,
+            key_miss_bg: CLR_KEY_MISS_BG,
+            fingers: CLR_FINGERS,
+            players: CLR_PLAYERS.to_vec(),
+        }
+    }
+}
+
+impl Theme {
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        match key {
+            "exercise_fg" => Some(&mut self.exercise_fg),
+            "exercise_bg" => Some(&mut self.exercise_bg),
+            "entry_fg" => Some(&mut self.entry_fg),
+            "entry_bg" => Some(&mut self.entry_bg),
+            "error_fg" => Some(&mut self.error_fg),
+            "error_bg" => Some(&mut self.error_bg),
+            "highlight" => Some(&mut self.highlight),
+            "ghost_fg" => Some(&mut self.ghost_fg),
+            "ghost_bg" => Some(&mut self.ghost_bg),
+            "key_fg" => Some(&mut self.key_fg),
+            "key_next_fg" => Some(&mut self.key_next_fg),
+            "key_miss_bg" => Some(&mut self.key_miss_bg),
+            _ => None,
+        }
+    }
+}
+
+/// default mode from the config, used when no mode is picked on the command line
+pub enum Mode {
+    Words,
+    Time(u64),
+    Quote,
+    Endless,
+}
+
+/// color by name, or hex like #ff8800
+fn parse_color(s: &str) -> Option<Color> {
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+    Color::try_from(s).ok()
+}
+
+/// ~/ at the start of a path is the home directory
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+/// path of the config file, e.g. ~/.config/tecken/config.toml
+pub fn config_path() -> Option<PathBuf> {
+    utils::config_dir().map(|d| d.join(CONFIG_FILE))
+}
+
+impl Tecken {
+    /// apply the config file, before the command line flags so those override it,
+    /// a missing file leaves the built-in defaults
+    pub fn load_config(&mut self) -> io::Result<Option<Mode>> {
+        let Some(path) = config_path() else {
+            return Ok(None);
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+
+        let invalid = |msg: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {msg}", path.display()),
+            )
+        };
+        let table: Table = text.parse().map_err(|e: toml::de::Error| invalid(e.to_string()))?;
+
+        let mut mode = None;
+        let mut time = None;
+        for (key, value) in &table {
+            let expected = |kind: &str| invalid(format!("'{key}' must be {kind}"));
+            match (key.as_str(), value) {
+                ("colors", Value::Table(colors)) => {
+                    for (key, value) in colors {
+                        let color_list = || -> io::Result<Vec<Color>> {
+                            value
+                                .as_array()
+                                .ok_or_else(|| {
+                                    invalid(format!("'{key}' must be a list of colors"))
+                                })?
+                                .iter()
+                                .map(|v| {
+                                    let name = v.as_str().ok_or_else(|| {
+                                        invalid(format!("'{key}' must be a list of colors"))
+                                    })?;
+                                    parse_color(name)
+                                        .ok_or_else(|| invalid(format!("unknown color '{name}'")))
+                                })
+                                .collect()
+                        };
+                        match key.as_str() {
+                            "fingers" => {
+                                self.theme.fingers = color_list()?.try_into().map_err(|_| {
+                                    invalid(format!(
+                                        "'{key}' must have {} colors",
+                                        CLR_FINGERS.len()
+                                    ))
+                                })?;
+                            }
+                            "players" => {
+                                let colors = color_list()?;
+                                if colors.is_empty() {
+                                    return Err(invalid(format!("'{key}' must have a color")));
+                                }
+                                self.theme.players = colors;
+                            }
+                            _ => {
+                                let name = value.as_str().ok_or_else(|| {
+                                    invalid(format!("'{key}' must be a color name"))
+                                })?;
+                                let color = self
+                                    .theme
+                                    .color_mut(key)
+                                    .ok_or_else(|| invalid(format!("unknown color '{key}'")))?;
+                                *color = parse_color(name)
+                                    .ok_or_else(|| invalid(format!("unknown color '{name}'")))?;
+                            }
+                        }
+                    }
+                }
+                ("mode", Value::String(m)) => {
+                    mode = Some(match m.as_str() {
+                        "words" => Mode::Words,
+                        "time" => Mode::Time(0),
+                        "quote" => Mode::Quote,
+                        "endless" => Mode::Endless,
+                        _ => return Err(expected("words, time, quote or endless")),
+                    });
+                }
+                ("words", Value::Integer(n)) if *n > 0 => self.f_word_quantity = *n as i32,
+                ("time", Value::Integer(n)) if *n > 0 => time = Some(*n as u64),
+                ("language", Value::String(lang)) => {
+                    if !WORDS.iter().any(|(l, _)| l == lang) {
+                        return Err(invalid(format!("unknown language '{lang}'")));
+                    }
+                    self.f_language = lang.clone();
+                }
+                ("difficulty", Value::Integer(n)) if *n >= 0 => {
+                    self.f_difficulty = (*n as usize).min(DIFFICULTY_TIERS.len() - 1);
+                }
+                ("wordlist", Value::String(p)) => self
+                    .load_wordlist(&expand_home(p))
+                    .map_err(|e| invalid(e.to_string()))?,
+                ("punctuation", Value::Boolean(b)) => self.f_punctuation = *b,
+                ("numbers", Value::Boolean(b)) => self.f_numbers = *b,
+                ("layout", Value::String(name)) => {
+                    self.layout =
+                        Layout::load(&expand_home(name)).map_err(|e| invalid(e.to_string()))?;
+                }
+                ("emulate", Value::Boolean(b)) => self.f_emulate_layout = *b,
+                ("keyboard", Value::Boolean(b)) => self.f_show_keyboard = *b,
+                ("hide_metadata", Value::Boolean(b)) => self.f_hide_metadata = *b,
+                ("metadata", Value::Array(fields)) => {
+                    self.f_metadata_fields = fields
+                        .iter()
+                        .map(|f| {
+                            let f = f.as_str().ok_or_else(|| expected("a list of fields"))?;
+                            MetaField::from_arg(f)
+                                .ok_or_else(|| invalid(format!("unknown metadata field '{f}'")))
+                        })
+                        .collect::<io::Result<Vec<MetaField>>>()?;
+                }
+                ("fps", Value::Integer(n)) if *n > 0 => self.fps = utils::get_fps(*n as f64),
+                ("colors", _) => return Err(expected("a table of colors")),
+                ("mode" | "language" | "wordlist" | "layout", _) => {
+                    return Err(expected("a string"));
+                }
+                ("words" | "time" | "difficulty" | "fps", _) => {
+                    return Err(expected("a positive number"));
+                }
+                ("punctuation" | "numbers" | "emulate" | "keyboard" | "hide_metadata", _) => {
+                    return Err(expected("true or false"));
+                }
+                ("metadata", _) => return Err(expected("a list of fields")),
+                _ => return Err(invalid(format!("unknown key '{key}'"))),
+            }
+        }
+
+        // the time only matters for time mode, which defaults to 30 seconds like -t
+        if let Some(Mode::Time(secs)) = &mut mode {
+            *secs = time.unwrap_or(30);
+        }
+        Ok(mode)
+    }
+}
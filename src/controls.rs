@@ -1,90 +1,197 @@
-use std::time::Duration;
+use std::{io, sync::mpsc, thread, time::Instant};
 
 use crate::{State, Tecken};
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, poll};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 
 impl Tecken {
-    pub fn controls(&mut self) -> std::io::Result<()> {
-        if poll(Duration::ZERO)? {
-            match self.state {
-                State::Main | State::Endless => {
-                    if let Event::Key(KeyEvent {
-                        code, modifiers, ..
-                    }) = event::read()?
-                    {
-                        match (code, modifiers) {
-                            // quit
-                            (KeyCode::Esc, _) => {
-                                self.state = State::Quit;
-                            }
+    /// read terminal events on their own thread, so every key is stamped with the time it
+    /// arrived instead of the frame it was handled in
+    pub fn spawn_event_reader(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            loop {
+                let ev = event::read();
+                let failed = ev.is_err();
+                if tx.send((Instant::now(), ev)).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        self.events = Some(rx);
+    }
 
-                            (
-                                KeyCode::Char('c'),
-                                KeyModifiers::CONTROL,
-                            ) => {
-                                self.state = State::Quit;
-                            }
+    pub fn controls(&mut self) -> io::Result<()> {
+        let Some(Ok((at, ev))) = self.events.as_ref().map(|events| events.try_recv()) else {
+            return Ok(());
+        };
+        let ev = ev?;
+        match self.state {
+            State::Main | State::Endless | State::Timed => {
+                if let Event::Resize(columns, rows) = ev {
+                    self.resize(columns, rows)?;
+                }
 
-                            // backspace
-                            (KeyCode::Backspace, KeyModifiers::ALT) => {
-                                _ = self.text_entry_buff.pop();
-                                let buff = self
-                                    .text_entry_buff
-                                    .clone();
-
-                                let mut count = 0;
-                                let mut i = 0;
-                                let mut space_found = false;
-                                for c in buff.chars().rev() {
-                                    if !space_found {
-                                        if c == ' ' {
-                                            space_found = true;
-                                            i = buff.chars()
-                                                .count()
-                                                - count;
-                                        }
-                                    }
-                                    count += 1;
-                                }
+                if let Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) = ev
+                {
+                    // tab arms the restart and next exercise hotkeys for one key press
+                    let tab_armed = std::mem::take(&mut self.tab_pressed);
+                    match (code, modifiers) {
+                        (KeyCode::Tab, _) => {
+                            self.tab_pressed = true;
+                        }
 
-                                if !space_found {
-                                    self.text_entry_buff
-                                        .clear();
-                                } else {
+                        // restart the same text, not in a race where the
+                        // clock starts at the countdown and the text is shared
+                        (KeyCode::Enter, _) if tab_armed && self.race.is_none() => {
+                            self.restart_exercise()?;
+                        }
+
+                        // new exercise
+                        (KeyCode::Char('n'), _) if tab_armed && self.race.is_none() => {
+                            self.reset_split();
+                            self.new_exercise()?;
+                        }
+
+                        // quit
+                        (KeyCode::Esc, _) => {
+                            self.state = State::Quit;
+                        }
+
+                        (
+                            KeyCode::Char('c'),
+                            KeyModifiers::CONTROL,
+                        ) => {
+                            self.state = State::Quit;
+                        }
+
+                        // backspace
+                        (KeyCode::Backspace, KeyModifiers::ALT) => {
+                            _ = self.text_entry_buff.pop();
+                            // keep everything up to and including the last space
+                            match self.text_entry_buff.rfind(' ') {
+                                Some(i) => {
                                     self.text_entry_buff
-                                        .truncate(i);
+                                        .truncate(i + 1);
                                 }
-                                if !self.input_registered {
-                                    self.input_registered =
-                                        true;
+                                None => {
+                                    self.text_entry_buff
+                                        .clear();
                                 }
                             }
-
-                            (KeyCode::Backspace, _) => {
-                                self.text_entry_buff.pop();
+                            if !self.input_registered {
+                                self.input_registered =
+                                    true;
                             }
+                        }
 
-                            // type characters
-                            (KeyCode::Char(c), _) => {
-                                if !self.first_char_typed {
-                                    self.first_char_typed =
-                                        true;
-                                }
-                                if !self.input_registered {
-                                    self.input_registered =
-                                        true;
-                                }
-                                self.text_entry_buff.push(c);
+                        (KeyCode::Backspace, _) => {
+                            self.text_entry_buff.pop();
+                        }
+
+                        // type characters
+                        (KeyCode::Char(c), _) => {
+                            if !self.first_char_typed {
+                                self.first_char_typed =
+                                    true;
+                                // the run starts when the first key arrived
+                                self.stopwatch.start_at(at);
+                            }
+                            if !self.input_registered {
+                                self.input_registered =
+                                    true;
                             }
+                            let c = self.emulate_key(c);
+                            self.record_keystroke(c, at);
+                            self.text_entry_buff.push(c);
+                        }
+
+                        _ => {}
+                    }
+                }
+            }
+
+            State::Lobby => {
+                if let Event::Resize(columns, rows) = ev {
+                    self.resize(columns, rows)?;
+                }
 
-                            _ => {}
+                if let Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) = ev
+                {
+                    match (code, modifiers) {
+                        // quit
+                        (KeyCode::Esc, _) => {
+                            self.state = State::Quit;
                         }
+
+                        (
+                            KeyCode::Char('c'),
+                            KeyModifiers::CONTROL,
+                        ) => {
+                            self.state = State::Quit;
+                        }
+
+                        // host starts the countdown
+                        (KeyCode::Enter, _) => {
+                            self.start_race();
+                        }
+
+                        _ => {}
                     }
                 }
+            }
 
-                State::Quit => {}
-                State::Help => {}
+            State::Results => {
+                if let Event::Resize(columns, rows) = ev {
+                    self.resize(columns, rows)?;
+                }
+
+                if let Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) = ev
+                {
+                    match (code, modifiers) {
+                        // quit
+                        (KeyCode::Esc, _) | (KeyCode::Char('q'), _) => {
+                            self.state = State::Quit;
+                        }
+
+                        (
+                            KeyCode::Char('c'),
+                            KeyModifiers::CONTROL,
+                        ) => {
+                            self.state = State::Quit;
+                        }
+
+                        // a race is over once finished
+                        _ if self.race.is_some() => {}
+
+                        // retry the same text
+                        (KeyCode::Enter, _) | (KeyCode::Char('r'), _) => {
+                            self.state = self.exercise_state();
+                            self.reset_split();
+                            self.restart_exercise()?;
+                        }
+
+                        // new exercise
+                        (KeyCode::Char('n'), _) | (KeyCode::Tab, _) => {
+                            self.state = self.exercise_state();
+                            self.reset_split();
+                            self.new_exercise()?;
+                        }
+
+                        _ => {}
+                    }
+                }
             }
+
+            State::Quit => {}
+            State::Help => {}
+            State::Stats => {}
+            State::Config => {}
         }
         Ok(())
     }
@@ -0,0 +1,67 @@
+use std::{
+    fs,
+    io::{self, IsTerminal, Read},
+};
+
+use rand::Rng;
+
+use crate::Tecken;
+
+impl Tecken {
+    /// load the text to practice on from a file, whitespace is normalized
+    pub fn load_text_file(&mut self, path: &str) -> io::Result<()> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("could not read '{path}': {e}")))?;
+        self.custom_text = text.split_whitespace().map(String::from).collect();
+        if self.custom_text.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("'{path}' contains no text"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// load a newline separated word list to use instead of the built-in word pool
+    pub fn load_wordlist(&mut self, path: &str) -> io::Result<()> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not read word list '{path}': {e}"))
+        })?;
+        self.custom_word_pool = text
+            .lines()
+            .flat_map(str::split_whitespace)
+            .map(String::from)
+            .collect();
+        if self.custom_word_pool.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("word list '{path}' contains no words"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// use piped stdin as the text to practice on, if there is any
+    pub fn load_text_stdin(&mut self) -> io::Result<()> {
+        let mut stdin = io::stdin();
+        if stdin.is_terminal() {
+            return Ok(());
+        }
+        let mut text = String::new();
+        stdin.read_to_string(&mut text)?;
+        self.custom_text = text.split_whitespace().map(String::from).collect();
+        Ok(())
+    }
+
+    /// the whole text, or a random passage of `f_passage_len` consecutive words
+    pub fn pick_passage(&mut self) -> Vec<String> {
+        let total = self.custom_text.len();
+        match self.f_passage_len {
+            Some(len) if len > 0 && len < total => {
+                let start = self.rng.random_range(..=total - len);
+                self.custom_text[start..start + len].to_vec()
+            }
+            _ => self.custom_text.clone(),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    fs,
+    io::{self, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    QueueableCommand,
+    cursor::MoveTo,
+    style::{ResetColor, SetBackgroundColor, SetForegroundColor},
+};
+
+use crate::{Tecken, timing::Keystroke, utils};
+
+const GHOSTS_FILE: &str = "ghosts.tsv";
+
+/// keystroke timeline of the personal best run of a mode
+pub struct Ghost {
+    /// (milliseconds since the start, chars typed in the run)
+    timeline: Vec<(u64, usize)>,
+}
+
+impl Ghost {
+    fn from_keystrokes(keystrokes: &[Keystroke]) -> Self {
+        Self {
+            timeline: keystrokes
+                .iter()
+                .map(|k| (k.at.as_millis() as u64, k.idx + 1))
+                .collect(),
+        }
+    }
+
+    /// chars the ghost had typed after `elapsed`
+    pub fn chars_at(&self, elapsed: Duration) -> usize {
+        let ms = elapsed.as_millis() as u64;
+        let i = self.timeline.partition_point(|(at, _)| *at <= ms);
+        if i == 0 { 0 } else { self.timeline[i - 1].1 }
+    }
+
+    fn to_line(&self, key: &str) -> String {
+        let timeline: Vec<String> = self
+            .timeline
+            .iter()
+            .map(|(ms, chars)| format!("{ms}:{chars}"))
+            .collect();
+        format!("{key}\t{}", timeline.join(","))
+    }
+
+    fn from_line(line: &str) -> Option<(&str, Self)> {
+        let (key, timeline) = line.split_once('\t')?;
+        let timeline = timeline
+            .split(',')
+            .map(|p| {
+                let (ms, chars) = p.split_once(':')?;
+                Some((ms.parse().ok()?, chars.parse().ok()?))
+            })
+            .collect::<Option<Vec<(u64, usize)>>>()?;
+        Some((key, Self { timeline }))
+    }
+}
+
+/// ghost of the personal best for a mode key, e.g. "words 12"
+pub fn load(key: &str) -> Option<Ghost> {
+    let text = fs::read_to_string(utils::data_file(GHOSTS_FILE).ok()?).ok()?;
+    text.lines()
+        .filter_map(Ghost::from_line)
+        .find(|(k, _)| *k == key)
+        .map(|(_, g)| g)
+}
+
+/// replace the stored ghost of a mode key
+fn save(key: &str, ghost: &Ghost) -> io::Result<()> {
+    let path = utils::data_file(GHOSTS_FILE)?;
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    let mut lines: Vec<String> = text
+        .lines()
+        .filter(|l| l.split_once('\t').is_some_and(|(k, _)| k != key))
+        .map(String::from)
+        .collect();
+    lines.push(ghost.to_line(key));
+    let mut file = fs::File::create(path)?;
+    for line in lines {
+        writeln!(file, "{line}")?;
+    }
+    Ok(())
+}
+
+impl Tecken {
+    /// load the ghost for the current mode, endless mode has no personal best to race
+    /// and races and split mode have the other players instead
+    pub fn load_ghost(&mut self) {
+        self.ghost = if self.f_endless_mode || self.race.is_some() || self.split.is_some() {
+            None
+        } else {
+            load(&self.current_run().mode_key())
+        };
+    }
+
+    /// store the current run as the ghost to race from now on
+    pub fn save_ghost(&mut self, key: &str) {
+        let ghost = Ghost::from_keystrokes(&self.keystrokes);
+        if let Err(e) = save(key, &ghost) {
+            self.result_save_error = Some(format!("Could not save ghost: {e}"));
+        }
+        self.ghost = Some(ghost);
+    }
+
+    /// chars typed minus the chars the ghost had typed at this point of the run
+    pub fn ghost_delta(&self) -> Option<i64> {
+        let ghost = self.ghost.as_ref()?;
+        let typed = self.text_entry_buff.chars().count();
+        Some(typed as i64 - ghost.chars_at(self.stopwatch.current()) as i64)
+    }
+
+    /// highlight the char the ghost is at
+    pub fn w_ghost(&mut self) -> io::Result<()> {
+        let Some(ghost) = &self.ghost else {
+            return Ok(());
+        };
+        if !self.first_char_typed {
+            return Ok(());
+        }
+        let idx = ghost.chars_at(self.stopwatch.current());
+        let (Some(ch), Some(pos)) = (
+            self.exercise_text_text.chars().nth(idx),
+            self.char_idx_to_pos(idx),
+        ) else {
+            return Ok(());
+        };
+        self.sout.queue(MoveTo(pos.col, pos.row))?;
+        self.sout.queue(SetBackgroundColor(self.theme.ghost_bg))?;
+        self.sout.queue(SetForegroundColor(self.theme.ghost_fg))?;
+        self.sout.write_all(ch.to_string().as_bytes())?;
+        self.sout.queue(ResetColor)?;
+        Ok(())
+    }
+}
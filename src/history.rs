@@ -0,0 +1,96 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::utils;
+
+const HISTORY_FILE: &str = "history.tsv";
+
+/// a single finished run, stored as one tab separated line in the history file
+pub struct Run {
+    /// seconds since unix epoch
+    pub timestamp: u64,
+    /// words | time | quote | text | endless | learn | race | daily
+    pub mode: String,
+    /// word quantity, seconds in timed mode, sentences in endless mode, lesson number
+    /// or day of the daily challenge
+    pub amount: u64,
+    pub words: usize,
+    pub errors: i32,
+    pub wpm: f64,
+    pub raw_wpm: f64,
+    pub accuracy: f64,
+    pub time_sec: f64,
+    /// false for attempts that were restarted or quit before finishing
+    pub completed: bool,
+}
+
+impl Run {
+    /// key used to group runs for personal bests, e.g. "words 12" or "time 30"
+    pub fn mode_key(&self) -> String {
+        match self.mode.as_str() {
+            "quote" | "text" | "endless" => self.mode.clone(),
+            _ => format!("{} {}", self.mode, self.amount),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{:.2}\t{:.2}\t{:.2}\t{:.2}\t{}",
+            self.timestamp,
+            self.mode,
+            self.amount,
+            self.words,
+            self.errors,
+            self.wpm,
+            self.raw_wpm,
+            self.accuracy,
+            self.time_sec,
+            self.completed,
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut f = line.split('\t');
+        Some(Self {
+            timestamp: f.next()?.parse().ok()?,
+            mode: f.next()?.to_string(),
+            amount: f.next()?.parse().ok()?,
+            words: f.next()?.parse().ok()?,
+            errors: f.next()?.parse().ok()?,
+            wpm: f.next()?.parse().ok()?,
+            raw_wpm: f.next()?.parse().ok()?,
+            accuracy: f.next()?.parse().ok()?,
+            time_sec: f.next()?.parse().ok()?,
+            // runs recorded before abandoned attempts were tracked are all completed
+            completed: f.next().and_then(|v| v.parse().ok()).unwrap_or(true),
+        })
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// append a run to the history file, creating it if needed
+pub fn append(run: &Run) -> io::Result<()> {
+    let path = utils::data_file(HISTORY_FILE)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", run.to_line())?;
+    Ok(())
+}
+
+/// load all runs, skipping lines that can't be parsed
+pub fn load() -> Vec<Run> {
+    let Some(path) = utils::data_file(HISTORY_FILE).ok() else {
+        return Vec::new();
+    };
+    fs::read_to_string(path)
+        .map(|s| s.lines().filter_map(Run::from_line).collect())
+        .unwrap_or_default()
+}
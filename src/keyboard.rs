@@ -0,0 +1,117 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    QueueableCommand,
+    cursor::MoveTo,
+    style::{ResetColor, SetBackgroundColor, SetForegroundColor},
+};
+
+use crate::{FRAME_ROWS, Tecken};
+
+// how long a mistyped key stays highlighted
+const MISS_FLASH: Duration = Duration::from_millis(300);
+// 4 key rows and the space bar
+const KEYBOARD_ROWS: u16 = 5;
+
+impl Tecken {
+    /// key width (label and gap) that fits the terminal, None if the keyboard doesn't fit at all
+    fn key_width(&self) -> Option<u16> {
+        // the frame, a line of text, the metadata and the keyboard with the gap above it
+        let needed = 2 * FRAME_ROWS + 1 + self.metadata_rows() + KEYBOARD_ROWS + 1;
+        if self.area.height < needed {
+            return None;
+        }
+        let widest = self
+            .layout
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, (lower, _))| (i, lower.len() as u16))
+            .collect::<Vec<_>>();
+        // 3 wide keys with a gap, or just the label with a gap, plus the row stagger
+        [4u16, 2].into_iter().find(|w| {
+            widest
+                .iter()
+                .all(|(i, keys)| *i as u16 * w / 2 + keys * w + 2 < self.area.width)
+        })
+    }
+
+    /// rows taken up by the keyboard, including the gap above it
+    pub fn keyboard_rows(&self) -> u16 {
+        match (self.f_show_keyboard, self.key_width()) {
+            (true, Some(_)) => KEYBOARD_ROWS + 1,
+            _ => 0,
+        }
+    }
+
+    /// draw the keyboard below the metadata, the next key is colored by the finger that
+    /// should type it and recently mistyped keys flash
+    pub fn w_keyboard(&mut self, top_row: u16) -> io::Result<()> {
+        let Some(key_w) = self.key_width() else {
+            return Ok(());
+        };
+
+        let caret = self.text_entry_buff.chars().count();
+        let next = self.exercise_text_text.chars().nth(caret);
+        let next_pos = next.and_then(|c| self.layout.position(c));
+        let next_finger = next.and_then(|c| self.layout.finger(c));
+        let missed = self
+            .last_miss
+            .filter(|(_, at)| at.elapsed() < MISS_FLASH)
+            .and_then(|(c, _)| self.layout.position(c));
+
+        let width = self.layout.rows[0].0.len() as u16 * key_w;
+        let left = self.area.pos.col + (self.area.width / 2).saturating_sub(width / 2);
+
+        for (r, (lower, _)) in self.layout.rows.iter().enumerate() {
+            let row = top_row + r as u16;
+            let stagger = r as u16 * key_w / 2;
+            for (c, key) in lower.iter().enumerate() {
+                let col = left + stagger + c as u16 * key_w;
+                let label = if key_w == 4 {
+                    format!(" {key} ")
+                } else {
+                    key.to_string()
+                };
+
+                let is_next = next_pos.is_some_and(|(nr, nc, _)| nr == r && nc == c);
+                let is_missed = missed.is_some_and(|(mr, mc, _)| mr == r && mc == c);
+
+                self.sout.queue(MoveTo(col, row))?;
+                if is_missed {
+                    self.sout.queue(SetForegroundColor(self.theme.key_next_fg))?;
+                    self.sout.queue(SetBackgroundColor(self.theme.key_miss_bg))?;
+                } else if let (true, Some(finger)) = (is_next, next_finger) {
+                    self.sout.queue(SetForegroundColor(self.theme.key_next_fg))?;
+                    self.sout
+                        .queue(SetBackgroundColor(self.theme.fingers[finger as usize]))?;
+                } else {
+                    self.sout.queue(SetForegroundColor(self.theme.key_fg))?;
+                }
+                self.sout.write_all(label.as_bytes())?;
+                self.sout.queue(ResetColor)?;
+            }
+        }
+
+        // space bar
+        let space_w = width / 2;
+        let col = left + width / 4;
+        let row = top_row + KEYBOARD_ROWS - 1;
+        self.sout.queue(MoveTo(col, row))?;
+        if next == Some(' ') {
+            self.sout.queue(SetForegroundColor(self.theme.key_next_fg))?;
+            self.sout.queue(SetBackgroundColor(
+                self.theme.fingers[crate::layout::Finger::Thumb as usize],
+            ))?;
+        } else {
+            self.sout.queue(SetForegroundColor(self.theme.key_fg))?;
+        }
+        self.sout
+            .write_all(format!("{:^w$}", "space", w = space_w as usize).as_bytes())?;
+        self.sout.queue(ResetColor)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+};
+
+use crate::{Tecken, utils};
+
+const KEYS_FILE: &str = "keys.tsv";
+
+/// per key statistics: how often each expected char was typed,
+/// and what was typed instead when it was missed
+#[derive(Default)]
+pub struct KeyStats {
+    pub attempts: HashMap<char, u32>,
+    pub misses: HashMap<(char, char), u32>,
+}
+
+impl KeyStats {
+    pub fn is_empty(&self) -> bool {
+        self.attempts.is_empty() && self.misses.is_empty()
+    }
+
+    pub fn merge(&mut self, other: &KeyStats) {
+        for (ch, n) in &other.attempts {
+            *self.attempts.entry(*ch).or_default() += n;
+        }
+        for (pair, n) in &other.misses {
+            *self.misses.entry(*pair).or_default() += n;
+        }
+    }
+
+    /// total misses per expected char
+    pub fn misses_per_key(&self) -> HashMap<char, u32> {
+        let mut per_key: HashMap<char, u32> = HashMap::new();
+        for ((expected, _), n) in &self.misses {
+            *per_key.entry(*expected).or_default() += n;
+        }
+        per_key
+    }
+
+    /// error rate (0.0 - 1.0) over a set of chars, e.g. both cases of a key,
+    /// None if none of them were ever typed
+    pub fn error_rate(&self, chars: &[char]) -> Option<f64> {
+        let attempts: u32 = chars.iter().filter_map(|c| self.attempts.get(c)).sum();
+        if attempts == 0 {
+            return None;
+        }
+        let per_key = self.misses_per_key();
+        let misses: u32 = chars.iter().filter_map(|c| per_key.get(c)).sum();
+        Some((misses as f64 / attempts as f64).min(1.0))
+    }
+
+    /// chars most often typed instead of `expected`, most common first
+    pub fn confused_with(&self, expected: char) -> Vec<(char, u32)> {
+        let mut typed: Vec<(char, u32)> = self
+            .misses
+            .iter()
+            .filter(|((e, _), _)| *e == expected)
+            .map(|((_, t), n)| (*t, *n))
+            .collect();
+        typed.sort_by_key(|t| std::cmp::Reverse(t.1));
+        typed
+    }
+
+    // chars are stored as code points so whitespace survives the tab separated format
+    fn to_lines(&self) -> String {
+        let mut out = String::new();
+        for (ch, n) in &self.attempts {
+            out.push_str(&format!("hit\t{}\t{}\n", *ch as u32, n));
+        }
+        for ((expected, typed), n) in &self.misses {
+            out.push_str(&format!(
+                "miss\t{}\t{}\t{}\n",
+                *expected as u32, *typed as u32, n
+            ));
+        }
+        out
+    }
+
+    fn from_lines(text: &str) -> Self {
+        let mut stats = Self::default();
+        let to_char = |s: &str| s.parse::<u32>().ok().and_then(char::from_u32);
+        for line in text.lines() {
+            let f: Vec<&str> = line.split('\t').collect();
+            match f.as_slice() {
+                ["hit", ch, n] => {
+                    if let (Some(ch), Ok(n)) = (to_char(ch), n.parse::<u32>()) {
+                        *stats.attempts.entry(ch).or_default() += n;
+                    }
+                }
+                ["miss", expected, typed, n] => {
+                    if let (Some(e), Some(t), Ok(n)) =
+                        (to_char(expected), to_char(typed), n.parse::<u32>())
+                    {
+                        *stats.misses.entry((e, t)).or_default() += n;
+                    }
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+}
+
+/// load the key statistics of all runs
+pub fn load() -> KeyStats {
+    utils::data_file(KEYS_FILE)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .map(|s| KeyStats::from_lines(&s))
+        .unwrap_or_default()
+}
+
+/// add the statistics of a run to the stored ones
+pub fn save(run: &KeyStats) -> io::Result<()> {
+    let path = utils::data_file(KEYS_FILE)?;
+    let mut all = load();
+    all.merge(run);
+    let mut file = fs::File::create(path)?;
+    file.write_all(all.to_lines().as_bytes())?;
+    Ok(())
+}
+
+impl Tecken {
+    /// count every char of the exercise the user has typed so far
+    pub fn record_key_attempts(&mut self) {
+        let typed = self.text_entry_buff.chars().count();
+        for ch in self.exercise_text_text.chars().take(typed) {
+            *self.key_stats.attempts.entry(ch).or_default() += 1;
+        }
+    }
+
+    /// store the key statistics of the current run and start over
+    pub fn save_key_stats(&mut self) {
+        self.record_key_attempts();
+        let run = std::mem::take(&mut self.key_stats);
+        if run.is_empty() {
+            return;
+        }
+        if let Err(e) = save(&run) {
+            self.result_save_error = Some(format!("Could not save key statistics: {e}"));
+        }
+    }
+}
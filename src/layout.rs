@@ -0,0 +1,222 @@
+use std::{fs, io, path::Path};
+
+use crate::Tecken;
+
+/// keyboard rows of a layout, (unshifted, shifted) from the number row down,
+/// every row has as many keys as the matching qwerty row
+pub type Rows = [(&'static str, &'static str); 4];
+
+pub const QWERTY: Rows = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|"),
+    ("asdfghjkl;'", "ASDFGHJKL:\""),
+    ("zxcvbnm,./", "ZXCVBNM<>?"),
+];
+
+const DVORAK: Rows = [
+    ("`1234567890[]", "~!@#$%^&*(){}"),
+    ("',.pyfgcrl/=\\", "\"<>PYFGCRL?+|"),
+    ("aoeuidhtns-", "AOEUIDHTNS_"),
+    (";qjkxbmwvz", ":QJKXBMWVZ"),
+];
+
+const COLEMAK: Rows = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qwfpgjluy;[]\\", "QWFPGJLUY:{}|"),
+    ("arstdhneio'", "ARSTDHNEIO\""),
+    ("zxcvbkm,./", "ZXCVBKM<>?"),
+];
+
+const COLEMAK_DH: Rows = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qwfpbjluy;[]\\", "QWFPBJLUY:{}|"),
+    ("arstgmneio'", "ARSTGMNEIO\""),
+    ("zxcdvkh,./", "ZXCDVKH<>?"),
+];
+
+const WORKMAN: Rows = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qdrwbjfup;[]\\", "QDRWBJFUP:{}|"),
+    ("ashtgyneoi'", "ASHTGYNEOI\""),
+    ("zxmcvkl,./", "ZXMCVKL<>?"),
+];
+
+// bundled layouts: (name, rows)
+pub const LAYOUTS: [(&str, Rows); 5] = [
+    ("qwerty", QWERTY),
+    ("dvorak", DVORAK),
+    ("colemak", COLEMAK),
+    ("colemak-dh", COLEMAK_DH),
+    ("workman", WORKMAN),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+    Thumb,
+}
+impl Finger {
+    pub const ALL: [Finger; 9] = [
+        Finger::LeftPinky,
+        Finger::LeftRing,
+        Finger::LeftMiddle,
+        Finger::LeftIndex,
+        Finger::RightIndex,
+        Finger::RightMiddle,
+        Finger::RightRing,
+        Finger::RightPinky,
+        Finger::Thumb,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Finger::LeftPinky => "left pinky",
+            Finger::LeftRing => "left ring",
+            Finger::LeftMiddle => "left middle",
+            Finger::LeftIndex => "left index",
+            Finger::RightIndex => "right index",
+            Finger::RightMiddle => "right middle",
+            Finger::RightRing => "right ring",
+            Finger::RightPinky => "right pinky",
+            Finger::Thumb => "thumb",
+        }
+    }
+
+    /// touch typing finger for a key position, the number row sits one key further left
+    fn from_pos(row: usize, col: usize) -> Self {
+        let col = if row == 0 { col.saturating_sub(1) } else { col };
+        match col {
+            0 => Finger::LeftPinky,
+            1 => Finger::LeftRing,
+            2 => Finger::LeftMiddle,
+            3 | 4 => Finger::LeftIndex,
+            5 | 6 => Finger::RightIndex,
+            7 => Finger::RightMiddle,
+            8 => Finger::RightRing,
+            _ => Finger::RightPinky,
+        }
+    }
+}
+
+pub struct Layout {
+    pub name: String,
+    /// (unshifted, shifted) keys per row
+    pub rows: Vec<(Vec<char>, Vec<char>)>,
+}
+
+impl Layout {
+    fn from_rows(name: &str, rows: &Rows) -> Self {
+        Self {
+            name: name.to_string(),
+            rows: rows
+                .iter()
+                .map(|(l, u)| (l.chars().collect(), u.chars().collect()))
+                .collect(),
+        }
+    }
+
+    pub fn qwerty() -> Self {
+        Self::from_rows("qwerty", &QWERTY)
+    }
+
+    /// a bundled layout by name, or a custom layout from a file
+    pub fn load(name: &str) -> io::Result<Self> {
+        if let Some((name, rows)) = LAYOUTS.iter().find(|(n, _)| *n == name) {
+            return Ok(Self::from_rows(name, rows));
+        }
+        if Path::new(name).is_file() {
+            return Self::from_file(name);
+        }
+        let available: Vec<&str> = LAYOUTS.iter().map(|(n, _)| *n).collect();
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "unknown layout '{name}', available: {} (or a path to a layout file)",
+                available.join(", ")
+            ),
+        ))
+    }
+
+    /// a layout file has 4 lines, number row to bottom row,
+    /// each with the unshifted and shifted keys separated by a space
+    fn from_file(path: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("could not read layout '{path}': {e}")))?;
+
+        let mut rows = Vec::new();
+        for (i, line) in text.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+            let (lower, upper) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| invalid(format!("layout '{path}' line {}: expected two rows", i + 1)))?;
+            let lower: Vec<char> = lower.chars().collect();
+            let upper: Vec<char> = upper.trim().chars().collect();
+            let expected = QWERTY.get(i).map(|(l, _)| l.chars().count());
+            if expected != Some(lower.len()) || lower.len() != upper.len() {
+                return Err(invalid(format!(
+                    "layout '{path}' line {}: expected {} keys",
+                    i + 1,
+                    expected.unwrap_or(0)
+                )));
+            }
+            rows.push((lower, upper));
+        }
+        if rows.len() != QWERTY.len() {
+            return Err(invalid(format!(
+                "layout '{path}' must have {} rows",
+                QWERTY.len()
+            )));
+        }
+
+        let name = Path::new(path)
+            .file_stem()
+            .map_or(path.to_string(), |s| s.to_string_lossy().to_string());
+        Ok(Self { name, rows })
+    }
+
+    /// (row, col, shifted) of the key that types `ch`
+    pub fn position(&self, ch: char) -> Option<(usize, usize, bool)> {
+        self.rows.iter().enumerate().find_map(|(r, (lower, upper))| {
+            if let Some(c) = lower.iter().position(|k| *k == ch) {
+                Some((r, c, false))
+            } else {
+                upper.iter().position(|k| *k == ch).map(|c| (r, c, true))
+            }
+        })
+    }
+
+    pub fn key_at(&self, row: usize, col: usize, shifted: bool) -> Option<char> {
+        let (lower, upper) = self.rows.get(row)?;
+        if shifted { upper.get(col) } else { lower.get(col) }.copied()
+    }
+
+    /// finger that types `ch` when touch typing on this layout
+    pub fn finger(&self, ch: char) -> Option<Finger> {
+        if ch == ' ' {
+            return Some(Finger::Thumb);
+        }
+        self.position(ch).map(|(r, c, _)| Finger::from_pos(r, c))
+    }
+}
+
+impl Tecken {
+    /// when emulating, a key pressed on a qwerty keyboard types the char
+    /// at the same position in the target layout
+    pub fn emulate_key(&self, c: char) -> char {
+        if !self.f_emulate_layout {
+            return c;
+        }
+        Layout::qwerty()
+            .position(c)
+            .and_then(|(r, col, shifted)| self.layout.key_at(r, col, shifted))
+            .unwrap_or(c)
+    }
+}
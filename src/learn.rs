@@ -0,0 +1,209 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+};
+
+use rand::Rng;
+
+use crate::{Tecken, WORDS, layout::Layout, utils};
+
+const PROGRESS_FILE: &str = "learn.tsv";
+// real words needed before made-up syllables are no longer added to a lesson
+const MIN_REAL_WORDS: usize = 30;
+// made-up syllables added to lessons with too few real words
+const MADE_UP_WORDS: usize = 60;
+
+/// keys a lesson adds on top of the previous lessons
+enum Keys {
+    HomeRow,
+    TopRow,
+    BottomRow,
+    Numbers,
+    Symbols,
+}
+
+pub struct Lesson {
+    pub name: &'static str,
+    keys: Keys,
+    pub min_accuracy: f64,
+    pub min_wpm: f64,
+}
+
+pub const LESSONS: [Lesson; 5] = [
+    Lesson {
+        name: "home row",
+        keys: Keys::HomeRow,
+        min_accuracy: 95.0,
+        min_wpm: 15.0,
+    },
+    Lesson {
+        name: "top row",
+        keys: Keys::TopRow,
+        min_accuracy: 95.0,
+        min_wpm: 20.0,
+    },
+    Lesson {
+        name: "bottom row",
+        keys: Keys::BottomRow,
+        min_accuracy: 95.0,
+        min_wpm: 25.0,
+    },
+    Lesson {
+        name: "numbers",
+        keys: Keys::Numbers,
+        min_accuracy: 95.0,
+        min_wpm: 20.0,
+    },
+    Lesson {
+        name: "symbols",
+        keys: Keys::Symbols,
+        min_accuracy: 90.0,
+        min_wpm: 15.0,
+    },
+];
+
+impl Keys {
+    fn chars(&self, layout: &Layout) -> Vec<char> {
+        // the unshifted char of each key in a row, or the shifted one when only that matches,
+        // e.g. the digits on azerty
+        let keys = |row: usize, matches: fn(&char) -> bool| -> Vec<char> {
+            let (lower, upper) = &layout.rows[row];
+            lower
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| {
+                    Some(*c)
+                        .filter(matches)
+                        .or_else(|| upper.get(i).copied().filter(matches))
+                })
+                .collect()
+        };
+        match self {
+            Keys::HomeRow => keys(2, |c| c.is_alphabetic()),
+            Keys::TopRow => keys(1, |c| c.is_alphabetic()),
+            Keys::BottomRow => keys(3, |c| c.is_alphabetic()),
+            Keys::Numbers => keys(0, char::is_ascii_digit),
+            Keys::Symbols => layout
+                .rows
+                .iter()
+                .flat_map(|(lower, upper)| lower.iter().chain(upper.iter()))
+                .copied()
+                .filter(|c| c.is_ascii_punctuation())
+                .collect(),
+        }
+    }
+}
+
+/// name of the first lesson up to `lesson` without any keys on the layout
+pub fn lesson_without_keys(layout: &Layout, lesson: usize) -> Option<&'static str> {
+    LESSONS[..=lesson]
+        .iter()
+        .find(|l| l.keys.chars(layout).is_empty())
+        .map(|l| l.name)
+}
+
+/// index of the highest unlocked lesson
+pub fn load_progress() -> usize {
+    utils::data_file(PROGRESS_FILE)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|s| {
+            s.lines()
+                .find_map(|l| l.strip_prefix("unlocked\t"))
+                .and_then(|n| n.trim().parse::<usize>().ok())
+        })
+        .unwrap_or(0)
+        .min(LESSONS.len() - 1)
+}
+
+fn save_progress(unlocked: usize) -> io::Result<()> {
+    let path = utils::data_file(PROGRESS_FILE)?;
+    let mut file = fs::File::create(path)?;
+    writeln!(file, "unlocked\t{unlocked}")?;
+    Ok(())
+}
+
+impl Tecken {
+    /// words for a lesson, only using the keys of that lesson and the ones before it
+    pub fn lesson_words(&mut self, lesson: usize) -> Vec<String> {
+        let allowed: HashSet<char> = LESSONS[..=lesson]
+            .iter()
+            .flat_map(|l| l.keys.chars(&self.layout))
+            .collect();
+        let letters: Vec<char> = allowed.iter().copied().filter(|c| c.is_alphabetic()).collect();
+        let rng = &mut self.rng;
+
+        let words = WORDS
+            .iter()
+            .find(|(lang, _)| *lang == self.f_language)
+            .map_or(WORDS[0].1, |(_, words)| words);
+        let mut pool: Vec<String> = words
+            .split_whitespace()
+            .filter(|w| w.chars().all(|c| allowed.contains(&c)))
+            .map(String::from)
+            .collect();
+
+        // made-up syllables when the word list has too few words for the keys
+        if pool.len() < MIN_REAL_WORDS {
+            for _ in 0..MADE_UP_WORDS {
+                let len = rng.random_range(2..=5);
+                pool.push(
+                    (0..len)
+                        .map(|_| letters[rng.random_range(..letters.len())])
+                        .collect(),
+                );
+            }
+        }
+
+        // numbers and symbols are practiced on their own and attached to words,
+        // with the plain words making up only a third of the pool
+        if let Keys::Numbers | Keys::Symbols = LESSONS[lesson].keys {
+            let extra = LESSONS[lesson].keys.chars(&self.layout);
+            let words = std::mem::take(&mut pool);
+            for _ in 0..MADE_UP_WORDS {
+                let token: String = (0..rng.random_range(1..=3))
+                    .map(|_| extra[rng.random_range(..extra.len())])
+                    .collect();
+                let word = &words[rng.random_range(..words.len())];
+                pool.push(format!("{word}{token}"));
+                pool.push(token);
+                pool.push(word.clone());
+            }
+        }
+        pool
+    }
+
+    /// check the lesson thresholds and unlock the next lesson when passed
+    pub fn evaluate_lesson(&mut self, wpm: f64, accuracy: f64) {
+        let Some(idx) = self.f_lesson else {
+            return;
+        };
+        let lesson = &LESSONS[idx];
+        if wpm < lesson.min_wpm || accuracy < lesson.min_accuracy {
+            self.result_lesson_msg = Some(format!(
+                "Lesson not passed, it needs {:.0} WPM and {:.0}% accuracy",
+                lesson.min_wpm, lesson.min_accuracy
+            ));
+            return;
+        }
+
+        let next = idx + 1;
+        if next >= LESSONS.len() {
+            self.result_lesson_msg = Some("Lesson passed, that was the last one!".to_string());
+            return;
+        }
+        if next > load_progress() {
+            if let Err(e) = save_progress(next) {
+                self.result_save_error = Some(format!("Could not save lesson progress: {e}"));
+            }
+            self.result_lesson_msg = Some(format!(
+                "Lesson passed! Unlocked lesson {}: {}",
+                next + 1,
+                LESSONS[next].name
+            ));
+        } else {
+            self.result_lesson_msg = Some("Lesson passed!".to_string());
+        }
+    }
+}
@@ -1,31 +1,65 @@
 use std::{
     collections::HashSet,
     io::{self, Stdout, Write, stdout},
+    sync::mpsc,
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crossterm::{
     QueueableCommand,
     cursor::MoveTo,
+    event::Event,
     style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
 };
-use rand::Rng;
+use rand::{
+    Rng, SeedableRng,
+    distr::{Distribution, weighted::WeightedIndex},
+    rngs::StdRng,
+};
 
+mod adaptive;
 mod arg_parse;
+mod config;
 mod controls;
+mod custom_text;
+mod ghost;
+mod history;
+mod keyboard;
+mod keystats;
+mod layout;
+mod learn;
+mod punctuation;
+mod quote;
+mod race;
+mod results;
+mod session;
+mod split;
 mod stopwatch;
 mod subcommands;
+mod timing;
 mod utils;
 
 // === constants ===
 
 // general
 
-const WORDS: &str = include_str!("static/words.txt");
+// word lists: (language, words)
+const WORDS: [(&str, &str); 5] = [
+    ("english", include_str!("static/words/english.txt")),
+    ("swedish", include_str!("static/words/swedish.txt")),
+    ("spanish", include_str!("static/words/spanish.txt")),
+    ("portuguese", include_str!("static/words/portuguese.txt")),
+    ("german", include_str!("static/words/german.txt")),
+];
+const QUOTES: &str = include_str!("static/quotes.txt");
 const FPS: f64 = 150.0;
+// rows kept free for the frame, on top of the metadata rows
+const FRAME_ROWS: u16 = 2;
+// word pool size for each difficulty (most common words first)
+const DIFFICULTY_TIERS: [usize; 5] = [200, 1000, 5000, 10000, 25000];
 
-// colors
+// colors, the defaults of the theme in the config file
 
 const CLR_ERROR_BG: Color = Color::Red;
 const CLR_ERROR_FG: Color = Color::Black;
@@ -33,6 +67,33 @@ const CLR_EXERCISE_BG: Color = Color::Reset;
 const CLR_EXERCISE_FG: Color = Color::Blue;
 const CLR_ENTRY_BG: Color = Color::Blue;
 const CLR_ENTRY_FG: Color = Color::Black;
+const CLR_PB_FG: Color = Color::Green;
+const CLR_GHOST_BG: Color = Color::DarkGrey;
+const CLR_GHOST_FG: Color = Color::White;
+// race markers per player id, the host is player 0
+const CLR_PLAYERS: [Color; 6] = [
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Cyan,
+    Color::DarkYellow,
+    Color::DarkMagenta,
+];
+const CLR_KEY_FG: Color = Color::DarkGrey;
+const CLR_KEY_NEXT_FG: Color = Color::Black;
+const CLR_KEY_MISS_BG: Color = Color::Red;
+// next key highlight per finger, from left pinky to right pinky, then thumb
+const CLR_FINGERS: [Color; 9] = [
+    Color::Magenta,
+    Color::Blue,
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::DarkYellow,
+    Color::DarkCyan,
+    Color::DarkMagenta,
+    Color::Grey,
+];
 
 // === code ===
 
@@ -41,34 +102,68 @@ fn main() -> io::Result<()> {
 
     let mut t = Tecken::new(stdout);
 
-    t.parse_args()?;
+    if let Err(e) = t.parse_args() {
+        eprintln!("{}: {e}", subcommands::help::APP_NAME);
+        std::process::exit(1);
+    }
 
     if t.state == State::Help {
         t.s_help();
         return Ok(());
     }
 
+    if t.state == State::Stats {
+        if t.f_stats_keys {
+            t.s_stats_keys();
+        } else if t.f_stats_ngrams {
+            t.s_stats_ngrams();
+        } else {
+            t.s_stats();
+        }
+        return Ok(());
+    }
+
+    if t.state == State::Config {
+        if t.f_config_init {
+            t.s_config_init();
+        } else {
+            t.s_config();
+        }
+        return Ok(());
+    }
+
+    if let Err(e) = t.open_race() {
+        eprintln!("{}: {e}", subcommands::help::APP_NAME);
+        std::process::exit(1);
+    }
+
     t.setup()?;
 
     while t.state != State::Quit {
         t.controls()?;
+        t.race_update();
         if t.first_char_typed {
             t.stopwatch.start();
         }
-        if t.state == State::Main || t.state == State::Endless {
+        if t.state == State::Main || t.state == State::Endless || t.state == State::Timed {
             t.main_loop()?;
         }
+        if t.state == State::Lobby {
+            t.lobby_loop()?;
+        }
+        if t.state == State::Results {
+            t.results_loop()?;
+        }
         t.sout.flush()?;
         thread::sleep(t.fps);
     }
 
     t.quit_cleanup()?;
 
-    // if user exits prematurely or is exiting endless mode, don't print results
-    if !t.f_endless_mode {
-        if t.exercise_finished() {
-            t.print_results();
-        }
+    // finished runs are saved when the results are shown, quitting mid-run is recorded as abandoned
+    t.save_abandoned_run();
+    if t.f_endless_mode {
+        t.print_session_summary();
     }
     Ok(())
 }
@@ -77,16 +172,56 @@ fn main() -> io::Result<()> {
 enum State {
     Main,
     Endless,
+    Timed,
+    Lobby,
+    Results,
     Help,
+    Stats,
+    Config,
     Quit,
 }
 
+/// fields that can be shown in the metadata panel
+#[derive(Clone, Copy, PartialEq)]
+enum MetaField {
+    Time,
+    Errors,
+    Wpm,
+    Accuracy,
+    Progress,
+    Ghost,
+}
+impl MetaField {
+    const ALL: [MetaField; 6] = [
+        MetaField::Time,
+        MetaField::Errors,
+        MetaField::Wpm,
+        MetaField::Accuracy,
+        MetaField::Progress,
+        MetaField::Ghost,
+    ];
+
+    fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "time" => Some(Self::Time),
+            "errors" => Some(Self::Errors),
+            "wpm" => Some(Self::Wpm),
+            "acc" => Some(Self::Accuracy),
+            "progress" => Some(Self::Progress),
+            "ghost" => Some(Self::Ghost),
+            _ => None,
+        }
+    }
+}
+
 #[allow(unused)]
+#[derive(Clone, Copy)]
 enum BorderType {
     Single,
     Double,
 }
 
+#[derive(Clone)]
 struct Rect {
     pos: Pos,
     width: u16,
@@ -131,6 +266,8 @@ struct Tecken {
     sout: Stdout,
     columns: u16,
     rows: u16,
+    // region of the terminal the exercise is drawn in, all of it or one player's half
+    area: Rect,
     state: State,
     fps: Duration,
     stopwatch: stopwatch::StopWatch,
@@ -138,17 +275,88 @@ struct Tecken {
     first_char_typed: bool,
     // signal to update ui only when input registered
     input_registered: bool,
+    // signal that tab was pressed, arming the restart and next exercise hotkeys
+    tab_pressed: bool,
+    // terminal events with the time they arrived, read on their own thread
+    events: Option<mpsc::Receiver<(Instant, io::Result<Event>)>>,
+    // random source of the exercises, seeded so an exercise can be repeated
+    rng: StdRng,
+    seed: u64,
     word_pool: Vec<String>,
+    // adaptive mode: sampling weight of every word in the pool
+    word_weights: Vec<f64>,
     exercise_text_lines: Vec<Line>,
     exercise_text_text: String,
+    // scrolling viewport: index of the first visible line and how many lines fit
+    scroll_line: usize,
+    visible_lines: usize,
     text_entry_buff: String,
     user_typing_errors: i32,
     invalid_letters_col_pos: HashSet<u16>,
     line_length: i32,
+    // signal that the time limit was reached
+    time_up: bool,
+    // speed in wpm for every second of the run, and chars typed at the last sample
+    speed_samples: Vec<f64>,
+    sampled_chars: usize,
+    // results of the last finished run
+    last_run: Option<history::Run>,
+    result_is_pb: bool,
+    result_consistency: f64,
+    result_save_error: Option<String>,
+    // signal that the current run was saved when its results were shown
+    run_saved: bool,
+    // every typed char of the current run with its timestamp
+    keystrokes: Vec<timing::Keystroke>,
+    // expected chars typed and missed during the current run
+    key_stats: keystats::KeyStats,
+    // last mistyped key and when, flashed on the keyboard
+    last_miss: Option<(char, std::time::Instant)>,
+    // colors, from the config file or the built-in ones
+    theme: config::Theme,
+    // keyboard layout used for emulation, the keyboard and per finger statistics
+    layout: layout::Layout,
+    // personal best of the current mode to race against
+    ghost: Option<ghost::Ghost>,
+    // two players taking turns on the same text
+    split: Option<split::Split>,
+    // race on the local network
+    race: Option<race::Race>,
+    // outcome of a learn lesson shown with the results
+    result_lesson_msg: Option<String>,
+    // endless mode aggregate over all completed sentences
+    session: session::Session,
+    // author of the current quote in quote mode
+    quote_author: String,
+    // words of a text file or piped stdin to practice on instead of the word pool
+    custom_text: Vec<String>,
+    // words of a user supplied word list, replaces the built-in word pool
+    custom_word_pool: Vec<String>,
     // flags & subcommands
     f_word_quantity: i32,
     f_endless_mode: bool,
+    f_time_limit: Option<u64>,
+    f_language: String,
+    f_difficulty: usize,
+    f_quote_mode: bool,
+    f_quote_length: Option<quote::QuoteLength>,
+    f_passage_len: Option<usize>,
+    f_punctuation: bool,
+    f_numbers: bool,
+    f_adaptive: bool,
+    f_emulate_layout: bool,
+    f_show_keyboard: bool,
+    f_lesson: Option<usize>,
+    f_host: Option<u16>,
+    f_seed: Option<u64>,
+    // days since the unix epoch of the daily challenge
+    f_daily: Option<u64>,
+    f_join: Option<String>,
     f_hide_metadata: bool,
+    f_stats_keys: bool,
+    f_stats_ngrams: bool,
+    f_config_init: bool,
+    f_metadata_fields: Vec<MetaField>,
 }
 
 impl Tecken {
@@ -157,124 +365,214 @@ impl Tecken {
             sout,
             columns: 0,
             rows: 0,
+            area: Rect::new(Pos::new(0, 0), 0, 0, BorderType::Double),
             state: State::Main,
             fps: utils::get_fps(FPS),
             stopwatch: stopwatch::StopWatch::new(),
             first_char_typed: false,
             input_registered: false,
+            tab_pressed: false,
+            events: None,
+            rng: StdRng::seed_from_u64(0),
+            seed: 0,
             word_pool: Vec::new(),
+            word_weights: Vec::new(),
             exercise_text_text: String::new(),
             exercise_text_lines: Vec::new(),
+            scroll_line: 0,
+            visible_lines: 0,
             text_entry_buff: String::new(),
             user_typing_errors: 0,
             invalid_letters_col_pos: HashSet::new(),
             line_length: 0,
+            time_up: false,
+            speed_samples: Vec::new(),
+            sampled_chars: 0,
+            last_run: None,
+            result_is_pb: false,
+            result_consistency: 0.0,
+            result_save_error: None,
+            run_saved: false,
+            keystrokes: Vec::new(),
+            key_stats: keystats::KeyStats::default(),
+            last_miss: None,
+            theme: config::Theme::default(),
+            layout: layout::Layout::qwerty(),
+            ghost: None,
+            split: None,
+            race: None,
+            result_lesson_msg: None,
+            session: session::Session::default(),
+            quote_author: String::new(),
+            custom_text: Vec::new(),
+            custom_word_pool: Vec::new(),
             // flags & subcommands
             f_word_quantity: 12,
             f_endless_mode: false,
+            f_time_limit: None,
+            f_language: String::from("english"),
+            f_difficulty: 1,
+            f_quote_mode: false,
+            f_quote_length: None,
+            f_passage_len: None,
+            f_punctuation: false,
+            f_numbers: false,
+            f_adaptive: false,
+            f_emulate_layout: false,
+            f_show_keyboard: false,
+            f_lesson: None,
+            f_host: None,
+            f_seed: None,
+            f_daily: None,
+            f_join: None,
             f_hide_metadata: false,
+            f_stats_keys: false,
+            f_stats_ngrams: false,
+            f_config_init: false,
+            f_metadata_fields: MetaField::ALL.to_vec(),
         }
     }
 
-    /// WPM: (words - errors) / minutes
-    /// Accuracy (%): 1 - (errors / total characters)
-    /// Time (sec)
-    fn print_results(&mut self) {
-        let total_time_sec = self.stopwatch.total() as f64;
-        let minutes = total_time_sec / 60.0;
-
-        let total_words = self.exercise_text_text.split_whitespace().count() as f64;
-        let total_chars = self.exercise_text_text.chars().count() as f64;
-        let errors = self.user_typing_errors as f64;
-
-        let raw_wpm = (total_words).max(0.0) / minutes;
-        let wpm = (total_words - (errors / 2.0)).max(0.0) / minutes;
-        let accuracy = (1.0 - (errors / total_chars)) * 100.0;
-
-        println!("Raw WPM:    {:.1}", raw_wpm);
-        println!("WPM:        {:.1}", wpm);
-        println!("Accuracy:   {:.2}%", accuracy);
-        println!("Time:       {:.1} sec", total_time_sec);
+    fn gen_new_sentence(&mut self) {
+        let words = self.gen_words();
+        self.layout_words(words);
     }
 
-    fn gen_new_sentence(&mut self) {
-        let mut rng = rand::rng();
-
-        // get words from word pool
-        let words: Vec<String> = {
-            let mut words = Vec::new();
-            while words.len() < self.f_word_quantity as usize {
-                let r = rng.random_range(..self.word_pool.len());
-                words.push(self.word_pool[r].clone());
+    /// words of the next exercise from the text, quotes or word pool
+    fn gen_words(&mut self) -> Vec<String> {
+        if !self.custom_text.is_empty() {
+            self.pick_passage()
+        } else if self.f_quote_mode {
+            self.pick_quote()
+        } else {
+            let mut words = self.sample_words();
+            if self.f_numbers {
+                words = self.add_numbers(words);
+            }
+            if self.f_punctuation {
+                words = self.add_punctuation(words);
             }
             words
-        };
+        }
+    }
 
-        // calc approx. number of lines required (only used for centering text vertically)
-        let num_of_lines: i32 = {
-            let words_len = words.concat().chars().count();
-            let n = words_len as i32 / self.rows as i32;
-            n
-        };
+    /// get words from word pool
+    fn sample_words(&mut self) -> Vec<String> {
+        let rng = &mut self.rng;
+        // adaptive mode favors words with weak keys, otherwise every word is equally likely
+        let weighted = WeightedIndex::new(&self.word_weights).ok();
+        let mut words = Vec::new();
+        while words.len() < self.f_word_quantity as usize {
+            let r = match &weighted {
+                Some(w) => w.sample(rng),
+                None => rng.random_range(..self.word_pool.len()),
+            };
+            words.push(self.word_pool[r].clone());
+        }
+        words
+    }
 
-        let mut exercise_text_text = String::new();
-        let center_row = self.rows / 2;
-        let starting_row: u16 = center_row - (num_of_lines as u16 / 2);
-        let mut lines_added: i32 = 0;
-        let max_line_len = self.columns as i32 - (self.columns as i32 / 2);
-        let mut it = words.iter();
-        let mut init = true;
-        while let Some(word) = it.next() {
-            let mut line_str = Vec::new();
-            let mut line_len: i32 = 0;
+    /// wrap words into centered lines and build the exercise text from them
+    fn layout_words(&mut self, words: Vec<String>) {
+        let max_line_len = self.area.width as usize - (self.area.width as usize / 2);
 
-            // add inital word
-            if init {
-                line_str.push(word.to_string());
-                line_str.push(" ".to_string());
-                line_len += word.chars().count() as i32;
-                init = false;
-            }
+        // split words into lines first so the number of lines is known before centering
+        let mut lines: Vec<Vec<String>> = Vec::new();
+        let mut it = words.into_iter().peekable();
+        while let Some(word) = it.next() {
+            let mut line_len = word.chars().count();
+            let mut line_str = vec![word];
 
             // create a new line
-            while line_len < max_line_len {
-                if let Some(val) = it.next().as_deref() {
-                    line_str.push(val.to_string());
-                    line_str.push(" ".to_string());
+            while let Some(next) = it.peek() {
+                let next_len = next.chars().count();
+                if line_len + 1 + next_len > max_line_len {
+                    break;
                 }
-                line_len += word.chars().count() as i32 + 1;
+                line_len += 1 + next_len;
+                line_str.push(" ".to_string());
+                line_str.push(it.next().unwrap_or_default());
             }
-            lines_added += 1;
+            lines.push(line_str);
+        }
 
-            exercise_text_text.push_str(&line_str.concat());
-            line_str.pop();
+        // lines that don't fit are scrolled into view as the user types,
+        // the text is centered together with the metadata and keyboard below it
+        let below = self.metadata_rows() + self.keyboard_rows();
+        let reserved = 2 * FRAME_ROWS + below;
+        let max_visible = self.area.height.saturating_sub(reserved).max(1) as usize;
+        self.visible_lines = lines.len().min(max_visible);
+        self.scroll_line = 0;
+
+        let starting_row: u16 = self.area.pos.row
+            + (self.area.height.saturating_sub(self.visible_lines as u16 + below) / 2)
+                .max(FRAME_ROWS / 2);
+
+        let mut exercise_text_text = String::new();
+        for (i, line_str) in lines.into_iter().enumerate() {
             let line_str_as_string = line_str.concat();
+            exercise_text_text.push_str(&line_str_as_string);
+            exercise_text_text.push(' ');
             let pos = Pos::new(
                 self.center_line(line_str_as_string),
-                starting_row + lines_added as u16,
+                starting_row + i as u16,
             );
-
             self.exercise_text_lines.push(Line::new(line_str, pos));
         }
         _ = exercise_text_text.pop();
         self.exercise_text_text = exercise_text_text;
     }
 
+    /// screen row of a line, or None if it is scrolled out of view
+    fn line_row(&self, line_idx: usize) -> Option<u16> {
+        if line_idx < self.scroll_line || line_idx >= self.scroll_line + self.visible_lines {
+            return None;
+        }
+        let line = self.exercise_text_lines.get(line_idx)?;
+        Some(line.pos.row - self.scroll_line as u16)
+    }
+
+    /// index of the line the caret is currently on
+    fn caret_line(&self) -> usize {
+        let caret = self.text_entry_buff.chars().count();
+        let mut offset = 0usize;
+        for (i, line) in self.exercise_text_lines.iter().enumerate() {
+            // include the space at line change
+            offset += line.text.concat().chars().count() + 1;
+            if caret < offset {
+                return i;
+            }
+        }
+        self.exercise_text_lines.len().saturating_sub(1)
+    }
+
+    /// scroll so the caret line stays visible with one finished line above it
+    fn update_scroll(&mut self) {
+        let max_scroll = self.exercise_text_lines.len().saturating_sub(self.visible_lines);
+        let scroll = self.caret_line().saturating_sub(1).min(max_scroll);
+        if scroll != self.scroll_line {
+            self.scroll_line = scroll;
+            self.input_registered = true;
+        }
+    }
+
     fn w_exercise_text(&mut self) -> io::Result<()> {
-        self.sout.queue(SetForegroundColor(CLR_EXERCISE_FG))?;
-        self.sout.queue(SetBackgroundColor(CLR_EXERCISE_BG))?;
-        for line in self.exercise_text_lines.iter_mut() {
-            self.sout.queue(MoveTo(line.pos.col, line.pos.row))?;
-            let text = line.text.concat();
-            self.sout.write(text.as_bytes())?;
+        self.sout.queue(SetForegroundColor(self.theme.exercise_fg))?;
+        self.sout.queue(SetBackgroundColor(self.theme.exercise_bg))?;
+        for (i, line) in self.exercise_text_lines.iter().enumerate() {
+            if let Some(row) = self.line_row(i) {
+                self.sout.queue(MoveTo(line.pos.col, row))?;
+                let text = line.text.concat();
+                self.sout.write_all(text.as_bytes())?;
+            }
         }
         self.sout.queue(ResetColor)?;
         Ok(())
     }
 
     fn w_user_entry(&mut self) -> io::Result<()> {
-        self.sout.queue(SetForegroundColor(CLR_ENTRY_FG))?;
-        self.sout.queue(SetBackgroundColor(CLR_ENTRY_BG))?;
+        self.sout.queue(SetForegroundColor(self.theme.entry_fg))?;
+        self.sout.queue(SetBackgroundColor(self.theme.entry_bg))?;
 
         let user_chars: Vec<char> = self.text_entry_buff.chars().collect();
         let mut offset: usize = 0;
@@ -286,11 +584,13 @@ impl Tecken {
                 break;
             }
 
-            let end = (offset + line_len).min(user_chars.len());
-            let typed_segment: String = user_chars[offset..end].iter().collect();
+            if let Some(row) = self.line_row(i) {
+                let end = (offset + line_len).min(user_chars.len());
+                let typed_segment: String = user_chars[offset..end].iter().collect();
 
-            self.sout.queue(MoveTo(line.pos.col, line.pos.row))?;
-            self.sout.write_all(typed_segment.as_bytes())?;
+                self.sout.queue(MoveTo(line.pos.col, row))?;
+                self.sout.write_all(typed_segment.as_bytes())?;
+            }
 
             offset += line_len;
 
@@ -304,24 +604,22 @@ impl Tecken {
         Ok(())
     }
 
+    /// screen position of a character in the exercise, None if scrolled out of view
     fn char_idx_to_pos(&self, idx: usize) -> Option<Pos> {
         let mut offset = 0usize;
 
-        for line in &self.exercise_text_lines {
+        for (i, line) in self.exercise_text_lines.iter().enumerate() {
             let line_len = line.text.concat().chars().count();
+            let last = i == self.exercise_text_lines.len() - 1;
 
-            if idx < offset + line_len {
+            // the space at line change sits right after the end of the line
+            if idx < offset + line_len || (!last && idx == offset + line_len) {
                 let col = line.pos.col + (idx - offset) as u16;
-                let row = line.pos.row;
+                let row = self.line_row(i)?;
                 return Some(Pos::new(col, row));
             }
 
-            offset += line_len;
-
-            // skip space at line change to not add extra offset
-            if line_len != self.exercise_text_lines.len() - 1 {
-                offset += 1;
-            }
+            offset += line_len + 1;
         }
 
         None
@@ -341,8 +639,15 @@ impl Tecken {
             }
         }
 
-        for _ in new_invalids.difference(&self.invalid_letters_col_pos) {
+        for &i in new_invalids.difference(&self.invalid_letters_col_pos) {
             self.user_typing_errors += 1;
+            let i = i as usize;
+            self.last_miss = Some((user_chars[i], std::time::Instant::now()));
+            *self
+                .key_stats
+                .misses
+                .entry((exercise_chars[i], user_chars[i]))
+                .or_default() += 1;
         }
 
         self.invalid_letters_col_pos = new_invalids;
@@ -359,8 +664,8 @@ impl Tecken {
             (user_chars.get(idx), self.char_idx_to_pos(idx))
             {
                 self.sout.queue(MoveTo(pos.col, pos.row))?;
-                self.sout.queue(SetBackgroundColor(CLR_ERROR_BG))?;
-                self.sout.queue(SetForegroundColor(CLR_ERROR_FG))?;
+                self.sout.queue(SetBackgroundColor(self.theme.error_bg))?;
+                self.sout.queue(SetForegroundColor(self.theme.error_fg))?;
                 self.sout.write_all(ch.to_string().as_bytes())?;
             }
         }
@@ -369,23 +674,85 @@ impl Tecken {
         Ok(())
     }
 
-    fn w_metadata(&mut self) -> io::Result<()> {
-        // get the last row of the exercise text to adhere to
-        let prev_row: u16 = {
-            let last_line = self.exercise_text_lines.last().unwrap();
-            last_line.pos.row
-        };
+    /// metadata fields to show, the ghost only once there is a personal best to race
+    fn shown_metadata_fields(&self) -> Vec<MetaField> {
+        self.f_metadata_fields
+            .iter()
+            .copied()
+            .filter(|f| *f != MetaField::Ghost || self.ghost.is_some())
+            .collect()
+    }
 
-        let time_s = format!("Elapsed : {}", self.stopwatch.elapsed());
-        let err_s = format!("Errors : {}", self.user_typing_errors);
+    /// rows taken up by the metadata panel, including the gap above it
+    fn metadata_rows(&self) -> u16 {
+        if self.f_hide_metadata {
+            return 0;
+        }
+        let author = if self.f_quote_mode { 1 } else { 0 };
+        self.shown_metadata_fields().len() as u16 + author + 1
+    }
 
-        let time_col = self.center_line(time_s.clone());
-        let err_col = self.center_line(err_s.clone());
+    /// live WPM and accuracy use the same formulas as the results, on what is typed so far
+    fn metadata_field(&self, field: MetaField) -> String {
+        let typed_chars = self.text_entry_buff.chars().count();
+        // every typed space finishes a word in the current exercise
+        let typed_words = self.text_entry_buff.chars().filter(|c| *c == ' ').count();
+        match field {
+            MetaField::Time => match self.f_time_limit {
+                Some(limit) => format!(
+                    "Remaining : {}",
+                    self.stopwatch.remaining(Duration::from_secs(limit))
+                ),
+                None => format!("Elapsed : {}", self.stopwatch.elapsed()),
+            },
+            MetaField::Errors => format!("Errors : {}", self.user_typing_errors),
+            MetaField::Wpm => {
+                let minutes = self.stopwatch.current().as_secs_f64() / 60.0;
+                let wpm = results::wpm(typed_words, self.user_typing_errors, minutes);
+                format!("WPM : {:.0}", wpm)
+            }
+            MetaField::Accuracy => {
+                let accuracy = results::accuracy(self.user_typing_errors, typed_chars);
+                format!("Accuracy : {:.1}%", accuracy)
+            }
+            MetaField::Progress => match self.f_time_limit {
+                Some(_) => format!("Words : {typed_words}"),
+                None => format!(
+                    "Words : {}/{}",
+                    typed_words,
+                    self.exercise_text_text.split_whitespace().count()
+                ),
+            },
+            MetaField::Ghost => match self.ghost_delta() {
+                Some(d) if d > 0 => format!("Ghost : you are {d} chars ahead"),
+                Some(d) if d < 0 => format!("Ghost : you are {} chars behind", -d),
+                _ => "Ghost : even".to_string(),
+            },
+        }
+    }
 
-        self.sout.queue(MoveTo(time_col, prev_row + 2))?;
-        self.sout.write(time_s.as_bytes())?;
-        self.sout.queue(MoveTo(err_col, prev_row + 3))?;
-        self.sout.write(err_s.as_bytes())?;
+    fn w_metadata(&mut self) -> io::Result<()> {
+        // get the last visible row of the exercise text to adhere to
+        let prev_row: u16 = self
+            .line_row(self.scroll_line + self.visible_lines - 1)
+            .unwrap_or_default();
+
+        let mut lines: Vec<String> = self
+            .shown_metadata_fields()
+            .into_iter()
+            .map(|f| self.metadata_field(f))
+            .collect();
+        if self.f_quote_mode {
+            lines.push(format!("- {}", self.quote_author));
+        }
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let col = self.center_line(line.clone());
+            // pad so shrinking values don't leave stale characters behind
+            self.sout
+                .queue(MoveTo(col.saturating_sub(1), prev_row + 2 + i as u16))?;
+            self.sout.write_all(format!(" {line} ").as_bytes())?;
+        }
         Ok(())
     }
 
@@ -403,8 +770,8 @@ impl Tecken {
 
         let x0 = r.pos.col;
         let y0 = r.pos.row;
-        let w = r.width as u16;
-        let h = r.height as u16;
+        let w = r.width;
+        let h = r.height;
 
         // 1x1: just a corner char (pick top-left)
         if w == 1 && h == 1 {
@@ -457,45 +824,57 @@ impl Tecken {
     }
 
     fn main_loop(&mut self) -> io::Result<()> {
+        self.update_scroll();
         if self.input_registered {
             self.clear_screen()?;
         }
 
         // write surrounding frame ui
-        let main_frame = Rect::new(
-            Pos { col: 0, row: 0 },
-            self.columns,
-            self.rows,
-            BorderType::Double,
-        );
-        self.w_rect(main_frame)?;
+        self.w_rect(self.area.clone())?;
+        self.w_split_panes()?;
 
         self.w_exercise_text()?;
         self.w_user_entry()?;
         self.validation()?;
         self.w_errors()?;
+        self.w_ghost()?;
+        self.w_race_markers()?;
 
         if !self.f_hide_metadata {
             self.w_metadata()?;
         }
 
-        // if sentence is finished, exit program
-        if self.state == State::Main {
-            if self.first_char_typed {
-                if self.exercise_finished() {
-                    self.stopwatch.stop();
-                    self.state = State::Quit;
-                }
-            }
+        if self.f_show_keyboard {
+            // keyboard sits right below the metadata
+            let last_row = self
+                .line_row(self.scroll_line + self.visible_lines - 1)
+                .unwrap_or_default();
+            self.w_keyboard(last_row + 1 + self.metadata_rows() + 1)?;
         }
-        if self.state == State::Endless {
-            if self.first_char_typed {
-                if self.exercise_finished() {
-                    self.endless_mode_next_sentence()?;
-                }
-            }
+
+        if self.first_char_typed {
+            self.sample_speed();
         }
+
+        // whatever finishing the sentence changes is cleared on the next frame
         self.input_registered = false;
+
+        // if sentence is finished, show results
+        if (self.state == State::Main || self.state == State::Endless)
+            && self.first_char_typed
+            && self.exercise_finished()
+        {
+            self.finish_run();
+        }
+        if self.state == State::Timed && self.first_char_typed {
+            let limit = Duration::from_secs(self.f_time_limit.unwrap_or_default());
+            if self.stopwatch.current() >= limit {
+                self.time_up = true;
+                self.finish_run();
+            } else if self.caret_line() + 1 >= self.exercise_text_lines.len() {
+                self.timed_mode_feed_words();
+            }
+        }
         // }
         Ok(())
     }
@@ -0,0 +1,72 @@
+use rand::Rng;
+
+use crate::Tecken;
+
+// chance (0.0 - 1.0) per word
+const CHANCE_NUMBER: f64 = 0.15;
+const CHANCE_COMMA: f64 = 0.12;
+const CHANCE_SENTENCE_END: f64 = 0.12;
+const CHANCE_QUOTES: f64 = 0.05;
+const CHANCE_PARENS: f64 = 0.05;
+
+impl Tecken {
+    /// replace some words with numeric tokens
+    pub fn add_numbers(&mut self, words: Vec<String>) -> Vec<String> {
+        let rng = &mut self.rng;
+        words
+            .into_iter()
+            .map(|w| {
+                if rng.random_bool(CHANCE_NUMBER) {
+                    let digits = rng.random_range(1..=4);
+                    rng.random_range(0..10u32.pow(digits)).to_string()
+                } else {
+                    w
+                }
+            })
+            .collect()
+    }
+
+    /// sprinkle in commas, sentence endings, quotes and parentheses,
+    /// capitalizing the first word of every sentence
+    pub fn add_punctuation(&mut self, words: Vec<String>) -> Vec<String> {
+        let rng = &mut self.rng;
+        let last = words.len().saturating_sub(1);
+        let mut capitalize_next = true;
+        let mut out = Vec::with_capacity(words.len());
+
+        for (i, word) in words.into_iter().enumerate() {
+            let mut word = if capitalize_next {
+                capitalize(&word)
+            } else {
+                word
+            };
+            capitalize_next = false;
+
+            if rng.random_bool(CHANCE_QUOTES) {
+                word = format!("\"{word}\"");
+            } else if rng.random_bool(CHANCE_PARENS) {
+                word = format!("({word})");
+            }
+
+            if i == last {
+                word.push('.');
+            } else if rng.random_bool(CHANCE_SENTENCE_END) {
+                let end = ['.', '.', '.', '?', '!'];
+                word.push(end[rng.random_range(..end.len())]);
+                capitalize_next = true;
+            } else if rng.random_bool(CHANCE_COMMA) {
+                word.push(',');
+            }
+            out.push(word);
+        }
+        out
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
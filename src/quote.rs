@@ -0,0 +1,50 @@
+use rand::Rng;
+
+use crate::{QUOTES, Tecken};
+
+// quote length boundaries (in characters)
+const QUOTE_SHORT_MAX: usize = 100;
+const QUOTE_MEDIUM_MAX: usize = 200;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum QuoteLength {
+    Short,
+    Medium,
+    Long,
+}
+impl QuoteLength {
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "short" => Some(Self::Short),
+            "medium" => Some(Self::Medium),
+            "long" => Some(Self::Long),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, quote: &str) -> bool {
+        let len = quote.chars().count();
+        match self {
+            Self::Short => len <= QUOTE_SHORT_MAX,
+            Self::Medium => len > QUOTE_SHORT_MAX && len <= QUOTE_MEDIUM_MAX,
+            Self::Long => len > QUOTE_MEDIUM_MAX,
+        }
+    }
+}
+
+impl Tecken {
+    /// pick a random quote (filtered by length if requested),
+    /// store its author and return the quote split into words
+    pub fn pick_quote(&mut self) -> Vec<String> {
+        // each line in the quote file is formatted as: quote|author
+        let quotes: Vec<(&str, &str)> = QUOTES
+            .lines()
+            .filter_map(|l| l.rsplit_once('|'))
+            .filter(|(q, _)| self.f_quote_length.is_none_or(|len| len.matches(q)))
+            .collect();
+
+        let (quote, author) = quotes[self.rng.random_range(..quotes.len())];
+        self.quote_author = author.trim().to_string();
+        quote.split_whitespace().map(String::from).collect()
+    }
+}
@@ -0,0 +1,522 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    QueueableCommand,
+    cursor::MoveTo,
+    style::{ResetColor, SetBackgroundColor, SetForegroundColor},
+};
+
+use crate::{BorderType, Pos, Rect, State, Tecken, history};
+
+pub const DEFAULT_PORT: u16 = 7878;
+const COUNTDOWN: Duration = Duration::from_secs(3);
+
+/// a connection sending and receiving newline terminated messages without blocking
+struct Peer {
+    stream: TcpStream,
+    pending: Vec<u8>,
+    /// bytes the socket couldn't take yet, written on the next send or recv
+    unsent: Vec<u8>,
+    closed: bool,
+}
+
+impl Peer {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            pending: Vec::new(),
+            unsent: Vec::new(),
+            closed: false,
+        })
+    }
+
+    fn send(&mut self, msg: &str) {
+        if self.closed {
+            return;
+        }
+        self.unsent.extend_from_slice(msg.as_bytes());
+        self.unsent.push(b'\n');
+        self.flush();
+    }
+
+    /// write as much of the unsent bytes as the socket takes without blocking
+    fn flush(&mut self) {
+        while !self.closed && !self.unsent.is_empty() {
+            match self.stream.write(&self.unsent) {
+                Ok(0) => self.closed = true,
+                Ok(n) => _ = self.unsent.drain(..n),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => self.closed = true,
+            }
+        }
+    }
+
+    /// all complete messages received since the last call
+    fn recv(&mut self) -> Vec<String> {
+        self.flush();
+        let mut buf = [0u8; 4096];
+        while !self.closed {
+            match self.stream.read(&mut buf) {
+                Ok(0) => self.closed = true,
+                Ok(n) => self.pending.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => self.closed = true,
+            }
+        }
+
+        let mut msgs = Vec::new();
+        while let Some(i) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=i).collect();
+            msgs.push(String::from_utf8_lossy(&line[..i]).to_string());
+        }
+        msgs
+    }
+}
+
+enum Role {
+    /// clients with their player id
+    Host {
+        listener: TcpListener,
+        port: u16,
+        clients: Vec<(usize, Peer)>,
+    },
+    Client {
+        peer: Peer,
+        addr: String,
+    },
+}
+
+#[derive(Default)]
+pub struct Player {
+    /// chars typed of the shared exercise
+    pub progress: usize,
+    /// (wpm, accuracy, time in seconds) once finished
+    pub result: Option<(f64, f64, f64)>,
+    pub left: bool,
+}
+
+/// a race on the local network, player 0 is the host
+///
+/// host -> client: welcome <id> | players <n> | text <exercise> | start <ms> |
+///                 progress <id> <chars> | result <id> <wpm> <acc> <sec> | left <id>
+/// client -> host: progress <chars> | result <wpm> <acc> <sec>
+pub struct Race {
+    role: Role,
+    /// id of the local player
+    pub id: usize,
+    pub players: Vec<Player>,
+    /// when the countdown ends and typing starts
+    pub start_at: Option<Instant>,
+    /// shown in the lobby and with the results, e.g. a lost connection
+    pub notice: Option<String>,
+}
+
+impl Race {
+    pub fn host(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|e| {
+            io::Error::new(e.kind(), format!("could not listen on port {port}: {e}"))
+        })?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            role: Role::Host {
+                listener,
+                port,
+                clients: Vec::new(),
+            },
+            id: 0,
+            players: vec![Player::default()],
+            start_at: None,
+            notice: None,
+        })
+    }
+
+    pub fn join(addr: &str) -> io::Result<Self> {
+        // the port can be left out when the host uses the default one
+        let addr = if addr.contains(':') {
+            addr.to_string()
+        } else {
+            format!("{addr}:{DEFAULT_PORT}")
+        };
+        let stream = TcpStream::connect(&addr)
+            .map_err(|e| io::Error::new(e.kind(), format!("could not join '{addr}': {e}")))?;
+        Ok(Self {
+            role: Role::Client {
+                peer: Peer::new(stream)?,
+                addr,
+            },
+            id: 0,
+            players: Vec::new(),
+            start_at: None,
+            notice: None,
+        })
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self.role, Role::Host { .. })
+    }
+
+    /// send a message to every client, except the one the message is about
+    fn broadcast(&mut self, msg: &str, except: Option<usize>) {
+        if let Role::Host { clients, .. } = &mut self.role {
+            for (id, peer) in clients.iter_mut() {
+                if Some(*id) != except {
+                    peer.send(msg);
+                }
+            }
+        }
+    }
+
+    fn send_to_host(&mut self, msg: &str) {
+        if let Role::Client { peer, .. } = &mut self.role {
+            peer.send(msg);
+        }
+    }
+
+    fn player(&mut self, id: usize) -> &mut Player {
+        if id >= self.players.len() {
+            self.players.resize_with(id + 1, Player::default);
+        }
+        &mut self.players[id]
+    }
+
+    /// host: send the exercise to everyone and start the countdown
+    pub fn start(&mut self, text: &str) {
+        self.broadcast(&format!("text {text}"), None);
+        self.broadcast(&format!("start {}", COUNTDOWN.as_millis()), None);
+        self.start_at = Some(Instant::now() + COUNTDOWN);
+    }
+
+    /// share the local progress when it changed
+    pub fn set_progress(&mut self, chars: usize) {
+        let id = self.id;
+        if self.players.get(id).is_some_and(|p| p.progress == chars) {
+            return;
+        }
+        self.player(id).progress = chars;
+        if self.is_host() {
+            self.broadcast(&format!("progress {id} {chars}"), None);
+        } else {
+            self.send_to_host(&format!("progress {chars}"));
+        }
+    }
+
+    pub fn set_result(&mut self, run: &history::Run) {
+        let id = self.id;
+        self.player(id).result = Some((run.wpm, run.accuracy, run.time_sec));
+        let result = format!("{:.2} {:.2} {:.2}", run.wpm, run.accuracy, run.time_sec);
+        if self.is_host() {
+            self.broadcast(&format!("result {id} {result}"), None);
+        } else {
+            self.send_to_host(&format!("result {result}"));
+        }
+    }
+
+    /// accept players and handle their messages, returns the exercise text when the
+    /// host sent it and whether anything changed
+    pub fn poll(&mut self) -> (Option<String>, bool) {
+        match &mut self.role {
+            Role::Host { .. } => (None, self.poll_host()),
+            Role::Client { .. } => self.poll_client(),
+        }
+    }
+
+    fn poll_host(&mut self) -> bool {
+        let mut changed = false;
+        let Role::Host {
+            listener, clients, ..
+        } = &mut self.role
+        else {
+            return false;
+        };
+
+        // players can only join before the race starts
+        while let Ok((stream, _)) = listener.accept() {
+            if self.start_at.is_some() {
+                continue;
+            }
+            let Ok(mut peer) = Peer::new(stream) else {
+                continue;
+            };
+            let id = self.players.len();
+            self.players.push(Player::default());
+            peer.send(&format!("welcome {id}"));
+            clients.push((id, peer));
+            changed = true;
+        }
+        if changed {
+            let msg = format!("players {}", self.players.len());
+            for (_, peer) in clients.iter_mut() {
+                peer.send(&msg);
+            }
+        }
+
+        let mut relay = Vec::new();
+        for (id, peer) in clients.iter_mut() {
+            for msg in peer.recv() {
+                let mut f = msg.split(' ');
+                match (f.next(), f.next(), f.next(), f.next()) {
+                    (Some("progress"), Some(chars), ..) => {
+                        if let Ok(chars) = chars.parse() {
+                            self.players[*id].progress = chars;
+                            relay.push((format!("progress {id} {chars}"), *id));
+                        }
+                    }
+                    (Some("result"), Some(wpm), Some(acc), Some(sec)) => {
+                        if let (Ok(wpm), Ok(acc), Ok(sec)) = (wpm.parse(), acc.parse(), sec.parse()) {
+                            self.players[*id].result = Some((wpm, acc, sec));
+                            relay.push((format!("result {id} {wpm} {acc} {sec}"), *id));
+                        }
+                    }
+                    _ => {}
+                }
+                changed = true;
+            }
+            if peer.closed && !self.players[*id].left {
+                self.players[*id].left = true;
+                relay.push((format!("left {id}"), *id));
+                changed = true;
+            }
+        }
+        for (msg, from) in relay {
+            self.broadcast(&msg, Some(from));
+        }
+        changed
+    }
+
+    fn poll_client(&mut self) -> (Option<String>, bool) {
+        let Role::Client { peer, .. } = &mut self.role else {
+            return (None, false);
+        };
+        let msgs = peer.recv();
+        let closed = peer.closed;
+
+        let mut text = None;
+        let mut changed = !msgs.is_empty();
+        for msg in msgs {
+            let (kind, rest) = msg.split_once(' ').unwrap_or((&msg, ""));
+            let f: Vec<&str> = rest.split(' ').collect();
+            let id = f.first().and_then(|n| n.parse::<usize>().ok());
+            match (kind, id) {
+                ("welcome", Some(id)) => self.id = id,
+                ("players", Some(n)) if n > self.players.len() => {
+                    self.players.resize_with(n, Player::default);
+                }
+                ("text", _) => text = Some(rest.to_string()),
+                ("start", Some(ms)) => {
+                    self.start_at = Some(Instant::now() + Duration::from_millis(ms as u64));
+                }
+                ("progress", Some(id)) => {
+                    if let Some(Ok(chars)) = f.get(1).map(|c| c.parse()) {
+                        self.player(id).progress = chars;
+                    }
+                }
+                ("result", Some(id)) => {
+                    let v: Vec<f64> = f[1..].iter().filter_map(|v| v.parse().ok()).collect();
+                    if let [wpm, acc, sec] = v[..] {
+                        self.player(id).result = Some((wpm, acc, sec));
+                    }
+                }
+                ("left", Some(id)) => self.player(id).left = true,
+                _ => {}
+            }
+        }
+        if closed && self.notice.is_none() {
+            self.notice = Some("Connection to the host was lost".to_string());
+            changed = true;
+        }
+        (text, changed)
+    }
+
+    pub fn player_name(&self, id: usize) -> String {
+        match id {
+            _ if id == self.id => "You".to_string(),
+            0 => "Host".to_string(),
+            _ => format!("Player {}", id + 1),
+        }
+    }
+
+    /// finished players by WPM, then everyone still typing
+    pub fn standings(&self) -> Vec<String> {
+        let mut finished: Vec<(usize, (f64, f64, f64))> = self
+            .players
+            .iter()
+            .enumerate()
+            .filter_map(|(id, p)| p.result.map(|r| (id, r)))
+            .collect();
+        finished.sort_by(|a, b| b.1.0.total_cmp(&a.1.0));
+
+        let mut lines: Vec<String> = finished
+            .iter()
+            .enumerate()
+            .map(|(place, (id, (wpm, acc, sec)))| {
+                format!(
+                    "{}. {:<10}: {wpm:.1} WPM, {acc:.2}%, {sec:.1} sec",
+                    place + 1,
+                    self.player_name(*id)
+                )
+            })
+            .collect();
+        for (id, p) in self.players.iter().enumerate() {
+            if p.result.is_none() {
+                let status = if p.left { "left" } else { "still typing" };
+                lines.push(format!("-. {:<10}: {status}", self.player_name(id)));
+            }
+        }
+        lines
+    }
+
+    fn lobby_lines(&self) -> Vec<String> {
+        let mut lines = match &self.role {
+            Role::Host { port, .. } => vec![
+                format!("Hosting a race on port {port}"),
+                format!("Others join with: tecken join <your ip>:{port}"),
+            ],
+            Role::Client { addr, .. } => vec![format!("Joined the race at {addr}")],
+        };
+        lines.push(format!("Players : {}", self.players.len()));
+        lines.push(String::new());
+        if let Some(notice) = &self.notice {
+            lines.push(notice.clone());
+        } else if self.is_host() {
+            lines.push("[Enter] start   [Esc] quit".to_string());
+        } else {
+            lines.push("Waiting for the host to start   [Esc] quit".to_string());
+        }
+        lines
+    }
+}
+
+impl Tecken {
+    /// host or join a race before the terminal is set up, so errors can be printed
+    pub fn open_race(&mut self) -> io::Result<()> {
+        let race = match (self.f_host, &self.f_join) {
+            (Some(port), _) => Race::host(port)?,
+            (None, Some(addr)) => Race::join(addr)?,
+            (None, None) => return Ok(()),
+        };
+        if self.f_time_limit.is_some() || self.f_endless_mode {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "races can't be combined with timed or endless mode",
+            ));
+        }
+        self.race = Some(race);
+        self.state = State::Lobby;
+        Ok(())
+    }
+
+    /// host: start the countdown on the current exercise
+    pub fn start_race(&mut self) {
+        let text = self.exercise_text_text.clone();
+        if let Some(race) = &mut self.race
+            && race.is_host()
+            && race.start_at.is_none()
+        {
+            race.start(&text);
+            self.input_registered = true;
+        }
+    }
+
+    /// exchange progress with the other players, and start typing once the countdown ends
+    pub fn race_update(&mut self) {
+        let Some(race) = &mut self.race else {
+            return;
+        };
+        let (text, changed) = race.poll();
+        if changed {
+            self.input_registered = true;
+        }
+        if let Some(text) = text {
+            self.exercise_text_text = text;
+            self.relayout();
+        }
+
+        let typed = self.text_entry_buff.chars().count();
+        let Some(race) = &mut self.race else {
+            return;
+        };
+        if self.state != State::Lobby {
+            race.set_progress(typed);
+        } else if race.start_at.is_some_and(|at| Instant::now() >= at) {
+            // the race clock starts for everyone at the same time
+            self.state = State::Main;
+            self.first_char_typed = true;
+            self.input_registered = true;
+        }
+    }
+
+    /// colored marker on the char each other player is at
+    pub fn w_race_markers(&mut self) -> io::Result<()> {
+        let Some(race) = &self.race else {
+            return Ok(());
+        };
+        let exercise: Vec<char> = self.exercise_text_text.chars().collect();
+        let mut markers = Vec::new();
+        for (id, p) in race.players.iter().enumerate() {
+            if id == race.id || p.left {
+                continue;
+            }
+            if let (Some(ch), Some(pos)) = (exercise.get(p.progress), self.char_idx_to_pos(p.progress)) {
+                markers.push((pos, *ch, self.theme.players[id % self.theme.players.len()]));
+            }
+        }
+
+        self.sout.queue(SetForegroundColor(self.theme.entry_fg))?;
+        for (pos, ch, color) in markers {
+            self.sout.queue(MoveTo(pos.col, pos.row))?;
+            self.sout.queue(SetBackgroundColor(color))?;
+            self.sout.write_all(ch.to_string().as_bytes())?;
+        }
+        self.sout.queue(ResetColor)?;
+        Ok(())
+    }
+
+    /// waiting room before the race, and the countdown over the shared exercise
+    pub fn lobby_loop(&mut self) -> io::Result<()> {
+        if self.input_registered {
+            self.clear_screen()?;
+            self.input_registered = false;
+        }
+        let main_frame = Rect::new(
+            Pos { col: 0, row: 0 },
+            self.columns,
+            self.rows,
+            BorderType::Double,
+        );
+        self.w_rect(main_frame)?;
+
+        let Some(race) = &self.race else {
+            return Ok(());
+        };
+        if let Some(at) = race.start_at {
+            let secs = at.saturating_duration_since(Instant::now()).as_secs() + 1;
+            let line = format!("Starting in {secs}");
+            let row = self
+                .line_row(self.scroll_line + self.visible_lines - 1)
+                .unwrap_or_default()
+                + 2;
+            self.w_exercise_text()?;
+            let col = self.center_line(line.clone());
+            self.sout.queue(MoveTo(col, row))?;
+            self.sout.write_all(line.as_bytes())?;
+            return Ok(());
+        }
+
+        let lines = race.lobby_lines();
+        let top = (self.rows / 2).saturating_sub(lines.len() as u16 / 2);
+        for (row, line) in (top..).zip(lines) {
+            let col = self.center_line(line.clone());
+            self.sout.queue(MoveTo(col, row))?;
+            self.sout.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
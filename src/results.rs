@@ -0,0 +1,291 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    QueueableCommand,
+    cursor::MoveTo,
+    style::{ResetColor, SetForegroundColor},
+};
+
+use crate::{BorderType, Pos, Rect, State, Tecken, history};
+
+/// WPM: (words - errors / 2) / minutes
+pub fn wpm(words: usize, errors: i32, minutes: f64) -> f64 {
+    if minutes <= 0.0 {
+        return 0.0;
+    }
+    (words as f64 - errors as f64 / 2.0).max(0.0) / minutes
+}
+
+/// Accuracy (%): 1 - (errors / characters)
+pub fn accuracy(errors: i32, chars: usize) -> f64 {
+    if chars == 0 {
+        return 100.0;
+    }
+    (1.0 - errors as f64 / chars as f64).max(0.0) * 100.0
+}
+
+impl Tecken {
+    /// a run is completed when the time limit is reached in timed mode,
+    /// or when the whole exercise is typed (endless mode never completes)
+    pub fn run_completed(&mut self) -> bool {
+        match self.f_time_limit {
+            Some(_) => self.time_up,
+            None => !self.f_endless_mode && self.first_char_typed && self.exercise_finished(),
+        }
+    }
+
+    /// WPM and accuracy, see `wpm` and `accuracy`
+    /// Time (sec)
+    ///
+    /// in timed mode and for abandoned runs, words and characters are what the user
+    /// managed to type, in timed mode the time is the fixed time limit
+    pub fn current_run(&mut self) -> history::Run {
+        let completed = self.run_completed();
+        let text_finished =
+            self.f_time_limit.is_none() && self.first_char_typed && self.exercise_finished();
+
+        // every typed space finishes a word in the current exercise
+        let typed_words = self.text_entry_buff.chars().filter(|c| *c == ' ').count();
+        let typed_chars = self.text_entry_buff.chars().count();
+
+        let (total_words, total_chars, total_time_sec) = match self.f_time_limit {
+            Some(limit) if completed => (typed_words, typed_chars, limit as f64),
+            None if text_finished => (
+                self.exercise_text_text.split_whitespace().count(),
+                self.exercise_text_text.chars().count(),
+                self.stopwatch.current().as_secs_f64(),
+            ),
+            _ => (
+                typed_words,
+                typed_chars,
+                self.stopwatch.current().as_secs_f64(),
+            ),
+        };
+        let minutes = total_time_sec / 60.0;
+
+        let raw_wpm = wpm(total_words, 0, minutes);
+        let wpm = wpm(total_words, self.user_typing_errors, minutes);
+        let accuracy = accuracy(self.user_typing_errors, total_chars);
+
+        let (mode, amount) = match (self.f_time_limit, self.f_quote_mode) {
+            (Some(limit), _) => ("time", limit),
+            _ if self.race.is_some() => ("race", total_words as u64),
+            // a single sentence of an endless session
+            _ if self.f_endless_mode => ("endless", 1),
+            _ if self.f_daily.is_some() => ("daily", self.f_daily.unwrap_or_default()),
+            _ if self.f_lesson.is_some() => ("learn", self.f_lesson.unwrap_or(0) as u64 + 1),
+            _ if !self.custom_text.is_empty() => ("text", total_words as u64),
+            (None, true) => ("quote", total_words as u64),
+            (None, false) => ("words", self.f_word_quantity as u64),
+        };
+
+        history::Run {
+            timestamp: history::now(),
+            mode: mode.to_string(),
+            amount,
+            words: total_words,
+            errors: self.user_typing_errors,
+            wpm,
+            raw_wpm,
+            accuracy,
+            time_sec: total_time_sec,
+            completed,
+        }
+    }
+
+    /// record a started but unfinished attempt in history
+    pub fn save_abandoned_run(&mut self) {
+        // split mode turns are shared by two people, so nothing of them is recorded
+        if self.split.is_some() {
+            return;
+        }
+        if !self.first_char_typed || self.run_saved || self.run_completed() {
+            return;
+        }
+        self.save_key_stats();
+        self.save_ngram_stats();
+        let run = self.current_run();
+        if let Err(e) = history::append(&run) {
+            eprintln!("Could not save run to history: {e}");
+        }
+    }
+
+    /// take a speed sample for every second that passed, used for consistency
+    pub fn sample_speed(&mut self) {
+        let secs = self.stopwatch.current().as_secs() as usize;
+        if secs <= self.speed_samples.len() {
+            return;
+        }
+        let typed_chars = self.text_entry_buff.chars().count();
+        let chars = typed_chars.saturating_sub(self.sampled_chars);
+        // 5 characters per word, 60 seconds per minute
+        self.speed_samples.push(chars as f64 / 5.0 * 60.0);
+        self.sampled_chars = typed_chars;
+    }
+
+    /// Consistency (%): 1 - (standard deviation / mean) of the per second speed
+    fn consistency(&self) -> f64 {
+        let n = self.speed_samples.len() as f64;
+        if n < 2.0 {
+            return 100.0;
+        }
+        let mean = self.speed_samples.iter().sum::<f64>() / n;
+        if mean == 0.0 {
+            return 0.0;
+        }
+        let variance = self.speed_samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+        ((1.0 - variance.sqrt() / mean) * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// stop the run, save it to history and show the results screen
+    pub fn finish_run(&mut self) {
+        self.stopwatch.stop();
+        let run = self.current_run();
+        if self.split.is_some() {
+            let consistency = self.consistency();
+            self.finish_turn(run, consistency);
+            return;
+        }
+
+        // endless mode sentences are shown and added to the session, the session is recorded on exit
+        self.result_is_pb = false;
+        self.result_save_error = None;
+        self.result_lesson_msg = None;
+        self.run_saved = true;
+        self.save_key_stats();
+        self.save_ngram_stats();
+        if self.f_endless_mode {
+            let chars = self.exercise_text_text.chars().count();
+            self.session.add(&run, chars);
+        } else {
+            let key = run.mode_key();
+            let best = history::load()
+                .into_iter()
+                .filter(|r| r.completed && r.mode_key() == key)
+                .map(|r| r.wpm)
+                .reduce(f64::max);
+            self.result_is_pb = best.is_none_or(|b| run.wpm > b);
+            if self.result_is_pb {
+                // the first ghost adds a metadata row, so the text moves up to make room
+                let had_ghost = self.ghost.is_some();
+                self.save_ghost(&key);
+                if !had_ghost {
+                    self.relayout();
+                }
+            }
+
+            if let Err(e) = history::append(&run) {
+                self.result_save_error = Some(format!("Could not save run to history: {e}"));
+            }
+        }
+
+        self.evaluate_lesson(run.wpm, run.accuracy);
+        if let Some(race) = &mut self.race {
+            race.set_progress(self.text_entry_buff.chars().count());
+            race.set_result(&run);
+        }
+        self.result_consistency = self.consistency();
+        self.last_run = Some(run);
+        self.state = State::Results;
+        self.input_registered = true;
+    }
+
+    /// state to return to when leaving the results screen
+    pub fn exercise_state(&self) -> State {
+        if self.f_endless_mode {
+            State::Endless
+        } else if self.f_time_limit.is_some() {
+            State::Timed
+        } else {
+            State::Main
+        }
+    }
+
+    pub fn results_loop(&mut self) -> io::Result<()> {
+        if self.input_registered {
+            self.clear_screen()?;
+            self.input_registered = false;
+        }
+        if self.split.is_some() {
+            return self.w_split_results();
+        }
+
+        let main_frame = Rect::new(
+            Pos { col: 0, row: 0 },
+            self.columns,
+            self.rows,
+            BorderType::Double,
+        );
+        self.w_rect(main_frame)?;
+        self.w_results()?;
+        Ok(())
+    }
+
+    fn w_results(&mut self) -> io::Result<()> {
+        let Some(run) = &self.last_run else {
+            return Ok(());
+        };
+
+        let mut lines = vec![
+            format!("WPM         : {:.1}", run.wpm),
+            format!("Raw WPM     : {:.1}", run.raw_wpm),
+            format!("Accuracy    : {:.2}%", run.accuracy),
+            format!("Time        : {:.1} sec", run.time_sec),
+            format!("Consistency : {:.2}%", self.result_consistency),
+            format!("Errors      : {}", run.errors),
+        ];
+        if self.f_quote_mode {
+            lines.push(format!("Quote by    : {}", self.quote_author));
+        }
+        // the other players of a race got their text from the host
+        if self.race.is_none() {
+            lines.push(format!("Seed        : {}", self.seed));
+        }
+        if let Some(race) = &self.race {
+            lines.push(String::new());
+            lines.extend(race.standings());
+            if let Some(notice) = &race.notice {
+                lines.push(String::new());
+                lines.push(notice.clone());
+            }
+        }
+        if let Some(msg) = &self.result_lesson_msg {
+            lines.push(String::new());
+            lines.push(msg.clone());
+        }
+        if let Some(e) = &self.result_save_error {
+            lines.push(String::new());
+            lines.push(e.clone());
+        }
+        lines.push(String::new());
+        if self.race.is_some() {
+            lines.push("[Esc] quit".to_string());
+        } else {
+            lines.push("[Enter] retry   [n] new test   [Esc] quit".to_string());
+        }
+
+        // header line is drawn above the results
+        let height = lines.len() as u16 + 2;
+        let mut row = self.area.pos.row + (self.area.height / 2).saturating_sub(height / 2);
+
+        if self.result_is_pb {
+            let pb_s = "New personal best!".to_string();
+            let col = self.center_line(pb_s.clone());
+            self.sout.queue(MoveTo(col, row))?;
+            self.sout.queue(SetForegroundColor(self.theme.highlight))?;
+            self.sout.write_all(pb_s.as_bytes())?;
+            self.sout.queue(ResetColor)?;
+        }
+        row += 2;
+
+        // align all lines on the same column, centered on the widest
+        let widest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let col = self.center_line(" ".repeat(widest));
+        for line in lines {
+            self.sout.queue(MoveTo(col, row))?;
+            self.sout.write_all(line.as_bytes())?;
+            row += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{Tecken, history, results};
+
+/// aggregate of all sentences completed during an endless mode session
+#[derive(Default)]
+pub struct Session {
+    pub sentences: usize,
+    pub words: usize,
+    pub chars: usize,
+    pub errors: i32,
+    pub time_sec: f64,
+    pub wpm_sum: f64,
+    pub raw_wpm_sum: f64,
+    pub best_wpm: f64,
+}
+
+impl Session {
+    pub fn add(&mut self, run: &history::Run, chars: usize) {
+        self.sentences += 1;
+        self.words += run.words;
+        self.chars += chars;
+        self.errors += run.errors;
+        self.time_sec += run.time_sec;
+        self.wpm_sum += run.wpm;
+        self.raw_wpm_sum += run.raw_wpm;
+        self.best_wpm = self.best_wpm.max(run.wpm);
+    }
+
+    /// accuracy over all sentences
+    fn accuracy(&self) -> f64 {
+        results::accuracy(self.errors, self.chars)
+    }
+
+    /// the session as a single history entry, WPM is the average over all sentences
+    fn to_run(&self) -> history::Run {
+        let n = self.sentences as f64;
+        history::Run {
+            timestamp: history::now(),
+            mode: "endless".to_string(),
+            amount: self.sentences as u64,
+            words: self.words,
+            errors: self.errors,
+            wpm: self.wpm_sum / n,
+            raw_wpm: self.raw_wpm_sum / n,
+            accuracy: self.accuracy(),
+            time_sec: self.time_sec,
+            completed: true,
+        }
+    }
+}
+
+impl Tecken {
+    /// print the endless mode session summary and save it to history
+    pub fn print_session_summary(&mut self) {
+        let s = &self.session;
+        if s.sentences == 0 {
+            return;
+        }
+
+        println!("Sentences:  {}", s.sentences);
+        println!("Words:      {}", s.words);
+        println!("Avg WPM:    {:.1}", s.wpm_sum / s.sentences as f64);
+        println!("Best WPM:   {:.1}", s.best_wpm);
+        println!("Accuracy:   {:.2}%", s.accuracy());
+        println!("Time:       {:.1} sec", s.time_sec);
+
+        if let Err(e) = history::append(&s.to_run()) {
+            eprintln!("Could not save session to history: {e}");
+        }
+    }
+}
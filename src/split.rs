@@ -0,0 +1,168 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    QueueableCommand,
+    cursor::MoveTo,
+    style::{ResetColor, SetForegroundColor},
+};
+
+use crate::{BorderType, Pos, Rect, State, Tecken, history};
+
+const PLAYERS: usize = 2;
+
+/// hot-seat mode, the players take turns on the same text in their half of the terminal
+#[derive(Default)]
+pub struct Split {
+    /// player currently typing
+    pub turn: usize,
+    /// finished turns with their consistency
+    runs: [Option<(history::Run, f64)>; PLAYERS],
+}
+
+impl Split {
+    /// player with the higher WPM once both finished, None on a tie
+    fn winner(&self) -> Option<usize> {
+        match &self.runs {
+            [Some((a, _)), Some((b, _))] if a.wpm > b.wpm => Some(0),
+            [Some((a, _)), Some((b, _))] if b.wpm > a.wpm => Some(1),
+            _ => None,
+        }
+    }
+}
+
+/// left half of the terminal for the first player, right half for the second
+pub fn pane(player: usize, columns: u16, rows: u16) -> Rect {
+    let half = columns / 2;
+    match player {
+        0 => Rect::new(Pos::new(0, 0), half, rows, BorderType::Double),
+        _ => Rect::new(Pos::new(half, 0), columns - half, rows, BorderType::Double),
+    }
+}
+
+impl Tecken {
+    /// keep the finished turn, then hand the same text to the next player or show both results
+    pub fn finish_turn(&mut self, run: history::Run, consistency: f64) {
+        let Some(split) = &mut self.split else {
+            return;
+        };
+        split.runs[split.turn] = Some((run, consistency));
+
+        if split.turn + 1 < PLAYERS {
+            split.turn += 1;
+            self.reset_attempt();
+            self.update_area();
+            self.relayout();
+        } else {
+            self.state = State::Results;
+        }
+        self.input_registered = true;
+    }
+
+    /// start over with the first player, e.g. for a rematch
+    pub fn reset_split(&mut self) {
+        let Some(split) = &mut self.split else {
+            return;
+        };
+        *split = Split::default();
+        self.update_area();
+        self.relayout();
+    }
+
+    /// title on the top border of a player's pane
+    fn w_pane_title(&mut self, area: &Rect, player: usize) -> io::Result<()> {
+        let title = format!(" Player {} ", player + 1);
+        let col = area.pos.col + (area.width / 2).saturating_sub(title.len() as u16 / 2);
+        self.sout.queue(MoveTo(col, area.pos.row))?;
+        self.sout.write_all(title.as_bytes())?;
+        Ok(())
+    }
+
+    /// lines centered in a pane, the first one highlighted when `highlight` is set
+    fn w_pane_lines(&mut self, area: &Rect, lines: &[String], highlight: bool) -> io::Result<()> {
+        let widest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+        let col = area.pos.col + (area.width / 2).saturating_sub(widest / 2);
+        let top = area.pos.row + (area.height / 2).saturating_sub(lines.len() as u16 / 2);
+        for (i, line) in lines.iter().enumerate() {
+            self.sout.queue(MoveTo(col, top + i as u16))?;
+            if i == 0 && highlight {
+                self.sout.queue(SetForegroundColor(self.theme.highlight))?;
+            }
+            self.sout.write_all(line.as_bytes())?;
+            self.sout.queue(ResetColor)?;
+        }
+        Ok(())
+    }
+
+    /// the pane of the player that isn't typing, and the titles of both
+    pub fn w_split_panes(&mut self) -> io::Result<()> {
+        let Some(split) = &self.split else {
+            return Ok(());
+        };
+        let turn = split.turn;
+        let other = (turn + 1) % PLAYERS;
+        let lines = match &split.runs[other] {
+            Some((run, _)) => vec![
+                "Finished".to_string(),
+                format!("WPM      : {:.1}", run.wpm),
+                format!("Accuracy : {:.2}%", run.accuracy),
+                format!("Time     : {:.1} sec", run.time_sec),
+            ],
+            None => vec!["Up next".to_string()],
+        };
+
+        let other_area = pane(other, self.columns, self.rows);
+        self.w_rect(other_area.clone())?;
+        self.w_pane_lines(&other_area, &lines, false)?;
+        self.w_pane_title(&self.area.clone(), turn)?;
+        self.w_pane_title(&other_area, other)?;
+        Ok(())
+    }
+
+    /// results of both players side by side
+    pub fn w_split_results(&mut self) -> io::Result<()> {
+        let Some(split) = &self.split else {
+            return Ok(());
+        };
+        let winner = split.winner();
+        let results: Vec<Vec<String>> = split
+            .runs
+            .iter()
+            .enumerate()
+            .map(|(player, r)| {
+                let Some((run, consistency)) = r else {
+                    return Vec::new();
+                };
+                let header = match winner {
+                    Some(w) if w == player => "Winner!",
+                    Some(_) => "",
+                    None => "Tie!",
+                };
+                vec![
+                    header.to_string(),
+                    String::new(),
+                    format!("WPM         : {:.1}", run.wpm),
+                    format!("Raw WPM     : {:.1}", run.raw_wpm),
+                    format!("Accuracy    : {:.2}%", run.accuracy),
+                    format!("Time        : {:.1} sec", run.time_sec),
+                    format!("Consistency : {:.2}%", consistency),
+                    format!("Errors      : {}", run.errors),
+                ]
+            })
+            .collect();
+
+        // the actions go on the last row, below both panes
+        let height = self.rows.saturating_sub(1);
+        for (player, lines) in results.iter().enumerate() {
+            let area = pane(player, self.columns, height);
+            self.w_rect(area.clone())?;
+            self.w_pane_title(&area, player)?;
+            self.w_pane_lines(&area, lines, true)?;
+        }
+
+        let actions = "[Enter] rematch   [n] new text   [Esc] quit";
+        let col = (self.columns / 2).saturating_sub(actions.len() as u16 / 2);
+        self.sout.queue(MoveTo(col, height))?;
+        self.sout.write_all(actions.as_bytes())?;
+        Ok(())
+    }
+}
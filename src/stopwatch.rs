@@ -16,9 +16,14 @@ impl StopWatch {
     }
 
     pub fn start(&mut self) {
+        self.start_at(Instant::now());
+    }
+
+    /// start as if started at `at`, e.g. when the key starting a run arrived
+    pub fn start_at(&mut self, at: Instant) {
         if !self.is_active {
             self.total = Duration::ZERO;
-            self.start = Some(Instant::now());
+            self.start = Some(at);
             self.is_active = true;
         }
     }
@@ -32,19 +37,29 @@ impl StopWatch {
         }
     }
 
-    pub fn elapsed(&self) -> String {
-        let duration = match (self.is_active, self.start) {
+    /// elapsed time including the currently running lap
+    pub fn current(&self) -> Duration {
+        match (self.is_active, self.start) {
             (true, Some(s)) => self.total + s.elapsed(),
             _ => self.total,
-        };
-        let second = format!("{:02}", duration.as_secs() % 60);
-        let minute = format!("{:02}", duration.as_secs() / 60);
-        format!("{minute}:{second}")
+        }
     }
 
-    pub fn total(&self) -> u64 {
-        let duration = self.total;
-        duration.as_secs()
+    /// elapsed time at `at`, for something that happened before now
+    pub fn at(&self, at: Instant) -> Duration {
+        match (self.is_active, self.start) {
+            (true, Some(s)) => self.total + at.saturating_duration_since(s),
+            _ => self.total,
+        }
+    }
+
+    pub fn elapsed(&self) -> String {
+        format_duration(self.current())
+    }
+
+    /// time left until `limit` is reached, formatted as mm:ss
+    pub fn remaining(&self, limit: Duration) -> String {
+        format_duration(limit.saturating_sub(self.current()))
     }
 
     pub fn reset(&mut self) {
@@ -52,3 +67,9 @@ impl StopWatch {
         self.is_active = false;
     }
 }
+
+fn format_duration(duration: Duration) -> String {
+    let second = format!("{:02}", duration.as_secs() % 60);
+    let minute = format!("{:02}", duration.as_secs() / 60);
+    format!("{minute}:{second}")
+}
@@ -0,0 +1,43 @@
+use std::fs;
+
+use crate::{
+    Tecken,
+    config::{self, DEFAULT_CONFIG},
+};
+
+impl Tecken {
+    /// print where the config file is read from
+    pub fn s_config(&mut self) {
+        let Some(path) = config::config_path() else {
+            println!("Could not determine the config directory.");
+            return;
+        };
+        if path.is_file() {
+            println!("Config file: {}", path.display());
+        } else {
+            println!("No config file at {}", path.display());
+            println!("Run `tecken config init` to write one with the defaults.");
+        }
+    }
+
+    /// write the commented default config, an existing file is left alone
+    pub fn s_config_init(&mut self) {
+        let Some(path) = config::config_path() else {
+            println!("Could not determine the config directory.");
+            return;
+        };
+        if path.exists() {
+            println!("A config file already exists at {}", path.display());
+            return;
+        }
+        let written = match path.parent() {
+            Some(parent) => fs::create_dir_all(parent),
+            None => Ok(()),
+        }
+        .and_then(|_| fs::write(&path, DEFAULT_CONFIG));
+        match written {
+            Ok(()) => println!("Wrote the default config to {}", path.display()),
+            Err(e) => println!("Could not write config to {}: {e}", path.display()),
+        }
+    }
+}
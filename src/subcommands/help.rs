@@ -10,7 +10,7 @@ pub const APP_AUTH: &str = env!("CARGO_PKG_AUTHORS");
 
 impl Tecken {
     pub fn s_help(&mut self) {
-        println!("");
+        println!();
         println!("{n} v{v}", n = APP_NAME, v = APP_VERS);
         println!("{APP_AUTH}");
         println!("{APP_REPO}");
@@ -1,2 +1,3 @@
-mod help;
-
+mod config;
+pub mod help;
+mod stats;
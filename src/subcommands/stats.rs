@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, io::IsTerminal};
+
+use crossterm::style::{Color, Stylize};
+
+use crate::{Tecken, history, keystats, layout::Finger, timing};
+
+// number of most recent runs used for recent averages
+const RECENT_RUNS: usize = 10;
+// number of keys listed in the weakest keys report, and attempts needed to be listed
+const WEAKEST_KEYS: usize = 10;
+const KEY_MIN_ATTEMPTS: u32 = 10;
+// number of ngrams listed per length, and samples needed to be listed
+const SLOWEST_NGRAMS: usize = 10;
+const NGRAM_MIN_SAMPLES: u32 = 3;
+
+impl Tecken {
+    pub fn s_stats(&mut self) {
+        let (runs, abandoned): (Vec<history::Run>, Vec<history::Run>) =
+            history::load().into_iter().partition(|r| r.completed);
+        if runs.is_empty() {
+            println!("No runs recorded yet, finish an exercise to start tracking statistics.");
+            return;
+        }
+
+        let recent = &runs[runs.len().saturating_sub(RECENT_RUNS)..];
+
+        println!();
+        println!("Runs:       {}", runs.len());
+        println!("Abandoned:  {}", abandoned.len());
+        println!("==========");
+        println!("All-time");
+        print_averages(&runs);
+        println!("==========");
+        println!("Last {}", recent.len());
+        print_averages(recent);
+        println!("==========");
+        println!("Personal bests");
+
+        // mode -> (runs, best run)
+        let mut modes: BTreeMap<String, (usize, &history::Run)> = BTreeMap::new();
+        for run in &runs {
+            let entry = modes.entry(run.mode_key()).or_insert((0, run));
+            entry.0 += 1;
+            if run.wpm > entry.1.wpm {
+                entry.1 = run;
+            }
+        }
+        for (mode, (count, best)) in &modes {
+            println!(
+                "{:<12}{:>6.1} WPM  {:>6.2}%  ({} runs)",
+                mode, best.wpm, best.accuracy, count
+            );
+        }
+    }
+}
+
+impl Tecken {
+    /// keyboard heatmap of error rates and the weakest keys
+    pub fn s_stats_keys(&mut self) {
+        let stats = keystats::load();
+        if stats.is_empty() {
+            println!("No key statistics recorded yet, finish an exercise to start tracking them.");
+            return;
+        }
+
+        // colors on a terminal, a mark after each key when written to a file or pipe
+        let colored = std::io::stdout().is_terminal();
+        let heat = |label: &str, rate: Option<f64>| match (colored, rate) {
+            (true, Some(rate)) => label.black().on(heat_color(rate)).to_string(),
+            (true, None) => label.dark_grey().to_string(),
+            (false, Some(rate)) => format!("{label}{}", heat_mark(rate)),
+            (false, None) => format!("{label} "),
+        };
+
+        println!();
+        println!("Error rate per key ({})", self.layout.name);
+        println!("==========");
+        for (i, (lower, upper)) in self.layout.rows.iter().enumerate() {
+            let mut row = " ".repeat(i * 2);
+            for (&l, &u) in lower.iter().zip(upper.iter()) {
+                let key = if colored {
+                    format!(" {l} ")
+                } else {
+                    format!(" {l}")
+                };
+                row.push_str(&heat(&key, stats.error_rate(&[l, u])));
+                row.push(' ');
+            }
+            println!("{row}");
+        }
+        let space_rate = stats.error_rate(&[' ']);
+        let space = if colored {
+            heat(&format!("{:^30}", "space"), space_rate)
+        } else {
+            format!("{:^30}", heat("space", space_rate))
+        };
+        println!("{}{space}", " ".repeat(12));
+        println!();
+        let legend: Vec<String> = [0.0, 0.02, 0.05, 0.1]
+            .into_iter()
+            .map(|rate| heat(if colored { "   " } else { "" }, Some(rate)))
+            .collect();
+        println!(
+            "{} < 2%  {} < 5%  {} < 10%  {} >= 10%",
+            legend[0], legend[1], legend[2], legend[3]
+        );
+
+        println!("==========");
+        println!("Error rate per finger");
+        for finger in Finger::ALL {
+            let chars: Vec<char> = stats
+                .attempts
+                .keys()
+                .copied()
+                .filter(|c| self.layout.finger(*c) == Some(finger))
+                .collect();
+            if let Some(rate) = stats.error_rate(&chars) {
+                println!("{:<14}{:>6.2}%", finger.name(), rate * 100.0);
+            }
+        }
+
+        println!("==========");
+        println!("Weakest keys");
+        let mut keys: Vec<(char, f64)> = stats
+            .attempts
+            .iter()
+            .filter(|(_, attempts)| **attempts >= KEY_MIN_ATTEMPTS)
+            .filter_map(|(c, _)| stats.error_rate(&[*c]).map(|r| (*c, r)))
+            .filter(|(_, r)| *r > 0.0)
+            .collect();
+        keys.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (ch, rate) in keys.into_iter().take(WEAKEST_KEYS) {
+            let attempts = stats.attempts.get(&ch).copied().unwrap_or(0);
+            let typed_instead: Vec<String> = stats
+                .confused_with(ch)
+                .into_iter()
+                .take(3)
+                .map(|(t, _)| key_name(t))
+                .collect();
+            println!(
+                "{:<7}{:>6.2}%  ({} typed)  typed instead: {}",
+                key_name(ch),
+                rate * 100.0,
+                attempts,
+                typed_instead.join(", ")
+            );
+        }
+    }
+}
+
+impl Tecken {
+    /// slowest bigrams and trigrams by average latency
+    pub fn s_stats_ngrams(&mut self) {
+        let stats = timing::load();
+        if stats.ngrams.is_empty() {
+            println!("No keystroke timings recorded yet, finish an exercise to start tracking them.");
+            return;
+        }
+
+        for (n, name) in [(2, "Slowest bigrams"), (3, "Slowest trigrams")] {
+            println!();
+            println!("{name}");
+            println!("==========");
+            let slowest = stats.slowest(n, NGRAM_MIN_SAMPLES);
+            if slowest.is_empty() {
+                println!("Not enough samples yet.");
+            }
+            for (ngram, ms, count) in slowest.into_iter().take(SLOWEST_NGRAMS) {
+                println!("{:<6}{:>7.0} ms  ({} samples)", ngram, ms, count);
+            }
+        }
+    }
+}
+
+fn key_name(ch: char) -> String {
+    match ch {
+        ' ' => "space".to_string(),
+        _ => ch.to_string(),
+    }
+}
+
+fn heat_color(rate: f64) -> Color {
+    if rate < 0.02 {
+        Color::Green
+    } else if rate < 0.05 {
+        Color::Yellow
+    } else if rate < 0.1 {
+        Color::DarkYellow
+    } else {
+        Color::Red
+    }
+}
+
+/// plain text stand-in for `heat_color`
+fn heat_mark(rate: f64) -> char {
+    if rate < 0.02 {
+        '.'
+    } else if rate < 0.05 {
+        ':'
+    } else if rate < 0.1 {
+        '+'
+    } else {
+        '#'
+    }
+}
+
+fn print_averages(runs: &[history::Run]) {
+    let n = runs.len() as f64;
+    let wpm = runs.iter().map(|r| r.wpm).sum::<f64>() / n;
+    let raw_wpm = runs.iter().map(|r| r.raw_wpm).sum::<f64>() / n;
+    let accuracy = runs.iter().map(|r| r.accuracy).sum::<f64>() / n;
+    let time = runs.iter().map(|r| r.time_sec).sum::<f64>();
+
+    println!("Raw WPM:    {:.1}", raw_wpm);
+    println!("WPM:        {:.1}", wpm);
+    println!("Accuracy:   {:.2}%", accuracy);
+    println!("Time:       {:.1} min", time / 60.0);
+}
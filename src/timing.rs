@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{Tecken, utils};
+
+const NGRAMS_FILE: &str = "ngrams.tsv";
+
+/// a single typed character
+#[derive(Clone)]
+pub struct Keystroke {
+    /// time since the stopwatch started
+    pub at: Duration,
+    /// position in the exercise text
+    pub idx: usize,
+    pub typed: char,
+    pub expected: Option<char>,
+}
+
+impl Keystroke {
+    fn correct(&self) -> bool {
+        self.expected == Some(self.typed)
+    }
+}
+
+/// latency per bigram and trigram: ngram -> (samples, total milliseconds)
+#[derive(Default)]
+pub struct NgramStats {
+    pub ngrams: HashMap<String, (u32, f64)>,
+}
+
+impl NgramStats {
+    /// average latency in milliseconds of ngrams of length `n` with at least `min_samples`,
+    /// slowest first
+    pub fn slowest(&self, n: usize, min_samples: u32) -> Vec<(String, f64, u32)> {
+        let mut slowest: Vec<(String, f64, u32)> = self
+            .ngrams
+            .iter()
+            .filter(|(g, (count, _))| g.chars().count() == n && *count >= min_samples)
+            .map(|(g, (count, total))| (g.clone(), total / *count as f64, *count))
+            .collect();
+        slowest.sort_by(|a, b| b.1.total_cmp(&a.1));
+        slowest
+    }
+
+    fn add(&mut self, ngram: String, ms: f64) {
+        let entry = self.ngrams.entry(ngram).or_default();
+        entry.0 += 1;
+        entry.1 += ms;
+    }
+
+    fn merge(&mut self, other: &NgramStats) {
+        for (g, (count, total)) in &other.ngrams {
+            let entry = self.ngrams.entry(g.clone()).or_default();
+            entry.0 += count;
+            entry.1 += total;
+        }
+    }
+
+    fn to_lines(&self) -> String {
+        let mut out = String::new();
+        for (g, (count, total)) in &self.ngrams {
+            out.push_str(&format!("{g}\t{count}\t{total:.1}\n"));
+        }
+        out
+    }
+
+    fn from_lines(text: &str) -> Self {
+        let mut stats = Self::default();
+        for line in text.lines() {
+            let mut f = line.split('\t');
+            if let (Some(g), Some(Ok(count)), Some(Ok(total))) = (
+                f.next(),
+                f.next().map(str::parse::<u32>),
+                f.next().map(str::parse::<f64>),
+            ) {
+                stats.ngrams.insert(g.to_string(), (count, total));
+            }
+        }
+        stats
+    }
+}
+
+/// bigram and trigram latencies of a run, only counting correct keystrokes typed in
+/// sequence within a word, the latency is the time from the first to the last key
+pub fn analyze(keystrokes: &[Keystroke]) -> NgramStats {
+    let mut stats = NgramStats::default();
+    for n in 2..=3 {
+        for w in keystrokes.windows(n) {
+            let in_sequence = w.windows(2).all(|p| p[1].idx == p[0].idx + 1);
+            let valid = w.iter().all(|k| k.correct() && !k.typed.is_whitespace());
+            if in_sequence && valid {
+                let ngram: String = w.iter().map(|k| k.typed).collect();
+                let ms = w[n - 1].at.saturating_sub(w[0].at).as_secs_f64() * 1000.0;
+                stats.add(ngram, ms);
+            }
+        }
+    }
+    stats
+}
+
+/// load the ngram latencies of all runs
+pub fn load() -> NgramStats {
+    utils::data_file(NGRAMS_FILE)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .map(|s| NgramStats::from_lines(&s))
+        .unwrap_or_default()
+}
+
+/// add the ngram latencies of a run to the stored ones
+pub fn save(run: &NgramStats) -> io::Result<()> {
+    let path = utils::data_file(NGRAMS_FILE)?;
+    let mut all = load();
+    all.merge(run);
+    let mut file = fs::File::create(path)?;
+    file.write_all(all.to_lines().as_bytes())?;
+    Ok(())
+}
+
+impl Tecken {
+    /// timestamp a typed character with the time it arrived, called before it is added to the
+    /// entry buffer
+    pub fn record_keystroke(&mut self, typed: char, arrived: Instant) {
+        let idx = self.text_entry_buff.chars().count();
+        self.keystrokes.push(Keystroke {
+            at: self.stopwatch.at(arrived),
+            idx,
+            typed,
+            expected: self.exercise_text_text.chars().nth(idx),
+        });
+    }
+
+    /// store the ngram latencies of the current run
+    pub fn save_ngram_stats(&mut self) {
+        let run = analyze(&self.keystrokes);
+        if run.ngrams.is_empty() {
+            return;
+        }
+        if let Err(e) = save(&run) {
+            self.result_save_error = Some(format!("Could not save keystroke timings: {e}"));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{io, time::Duration};
+use std::{env, fs, io, path::PathBuf, time::Duration};
 
 use crossterm::{
     ExecutableCommand, QueueableCommand, cursor,
@@ -7,16 +7,24 @@ use crossterm::{
         disable_raw_mode, enable_raw_mode,
     },
 };
+use rand::{Rng, SeedableRng, rngs::StdRng};
 
-use crate::{Tecken, WORDS, stopwatch::StopWatch};
+use crate::{
+    BorderType, DIFFICULTY_TIERS, Pos, Rect, Tecken, WORDS, keystats::KeyStats, split,
+    stopwatch::StopWatch, subcommands::help::APP_NAME,
+};
 
 impl Tecken {
     pub fn setup(&mut self) -> io::Result<()> {
         self.sout.execute(EnterAlternateScreen)?;
         (self.columns, self.rows) = terminal::size()?;
+        self.update_area();
         enable_raw_mode()?;
+        self.spawn_event_reader();
         self.clear_screen()?;
+        self.reseed(self.f_seed.unwrap_or_else(rand::random));
         self.gen_word_pool();
+        self.load_ghost();
         self.sout.queue(cursor::SavePosition)?;
         self.sout.queue(cursor::Hide)?;
         self.line_length = self.f_word_quantity / 2;
@@ -24,40 +32,147 @@ impl Tecken {
         Ok(())
     }
 
-    pub fn endless_mode_next_sentence(&mut self) -> io::Result<()> {
-        // state reset
+    /// reset everything the user typed so the exercise can be attempted again
+    pub fn reset_attempt(&mut self) {
         self.input_registered = false;
         self.first_char_typed = false;
         self.invalid_letters_col_pos.clear();
         self.text_entry_buff.clear();
-        self.exercise_text_text.clear();
-        self.exercise_text_lines.clear();
         self.user_typing_errors = 0;
+        self.time_up = false;
+        self.scroll_line = 0;
+        self.speed_samples.clear();
+        self.sampled_chars = 0;
+        self.run_saved = false;
+        self.key_stats = KeyStats::default();
+        self.keystrokes.clear();
         self.stopwatch.stop();
         self.stopwatch.reset();
         self.stopwatch = StopWatch::new();
+    }
+
+    pub fn endless_mode_next_sentence(&mut self) -> io::Result<()> {
+        // state reset
+        self.reset_attempt();
+        self.exercise_text_text.clear();
+        self.exercise_text_lines.clear();
 
-        // new setup
+        // new setup, the daily challenge always starts over on the same text
         self.clear_screen()?;
+        let seed = match self.f_daily {
+            Some(day) => day,
+            None => self.rng.random(),
+        };
+        self.reseed(seed);
         self.gen_word_pool();
         self.gen_new_sentence();
         Ok(())
     }
 
+    /// restart the random source, every exercise has its own seed so it can be shared
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// start over on the same text
+    pub fn restart_exercise(&mut self) -> io::Result<()> {
+        self.save_abandoned_run();
+        self.reset_attempt();
+        self.input_registered = true;
+        self.clear_screen()?;
+        Ok(())
+    }
+
+    /// drop the current attempt and generate a fresh exercise
+    pub fn new_exercise(&mut self) -> io::Result<()> {
+        self.save_abandoned_run();
+        self.endless_mode_next_sentence()?;
+        self.input_registered = true;
+        Ok(())
+    }
+
+    /// re-wrap the current exercise for a new terminal size,
+    /// typed progress and errors are kept as the text itself doesn't change
+    pub fn resize(&mut self, columns: u16, rows: u16) -> io::Result<()> {
+        self.columns = columns;
+        self.rows = rows;
+        self.update_area();
+        self.relayout();
+
+        self.input_registered = true;
+        self.clear_screen()?;
+        Ok(())
+    }
+
+    /// wrap the current exercise again, e.g. when the space around it changed
+    pub fn relayout(&mut self) {
+        let words: Vec<String> = self
+            .exercise_text_text
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        self.exercise_text_lines.clear();
+        self.layout_words(words);
+    }
+
+    /// keep a timed run going by appending words once the caret reaches the last line,
+    /// so the text never ends before the time limit is reached
+    pub fn timed_mode_feed_words(&mut self) {
+        let mut words: Vec<String> = self
+            .exercise_text_text
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        words.extend(self.gen_words());
+        self.exercise_text_lines.clear();
+        self.layout_words(words);
+        self.update_scroll();
+        self.input_registered = true;
+    }
+
     pub fn exercise_finished(&mut self) -> bool {
         self.text_entry_buff.chars().count() == self.exercise_text_text.chars().count()
     }
 
-    /// calculate the column pos required so that a line can be centered
+    /// the exercise takes the whole terminal, or the half of the current player in split mode
+    pub fn update_area(&mut self) {
+        self.area = match &self.split {
+            Some(split) => split::pane(split.turn, self.columns, self.rows),
+            None => Rect::new(Pos::new(0, 0), self.columns, self.rows, BorderType::Double),
+        };
+    }
+
+    /// calculate the column pos required so that a line can be centered in the area
     pub fn center_line(&mut self, line: String) -> u16 {
-        let center_of_vp = self.columns / 2;
+        let center_of_vp = self.area.pos.col + self.area.width / 2;
         let line_length = line.chars().count();
-        let centered = center_of_vp as usize - (line_length / 2);
-        return centered as u16;
+        let centered = (center_of_vp as usize).saturating_sub(line_length / 2);
+        centered as u16
     }
 
     pub fn gen_word_pool(&mut self) {
-        self.word_pool = WORDS.split_whitespace().map(String::from).collect();
+        // lessons and custom word lists replace the built-in pool entirely
+        if let Some(lesson) = self.f_lesson {
+            self.word_pool = self.lesson_words(lesson);
+        } else if !self.custom_word_pool.is_empty() {
+            self.word_pool = self.custom_word_pool.clone();
+        } else {
+            let words = WORDS
+                .iter()
+                .find(|(lang, _)| *lang == self.f_language)
+                .map_or(WORDS[0].1, |(_, words)| words);
+            // word lists are ordered by frequency, so difficulty caps the pool to the most common,
+            // a list shorter than the cap is used whole
+            self.word_pool = words
+                .split_whitespace()
+                .take(DIFFICULTY_TIERS[self.f_difficulty])
+                .map(String::from)
+                .collect();
+        }
+        if self.f_adaptive {
+            self.gen_word_weights();
+        }
     }
 
     pub fn quit_cleanup(&mut self) -> io::Result<()> {
@@ -77,3 +192,39 @@ impl Tecken {
 pub fn get_fps(fps: f64) -> Duration {
     Duration::from_secs_f64(1.0 / fps)
 }
+
+/// directory for persistent data, e.g. ~/.local/share/tecken
+fn data_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+            PathBuf::from(home).join(".local").join("share")
+        }
+    };
+    Some(base.join(APP_NAME))
+}
+
+/// path of a file in the data directory, creating the directory if needed
+pub fn data_file(name: &str) -> io::Result<PathBuf> {
+    let Some(dir) = data_dir() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "could not determine data directory",
+        ));
+    };
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// directory for the config file, e.g. ~/.config/tecken
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Some(base.join(APP_NAME))
+}